@@ -6,8 +6,9 @@
 //! 
 //! The following example shows the calculation of the sum of all transactions after a given date (up to the page limit).
 //! 
-//! ```
+//! ```no_run
 //! use up_api::v1::Client;
+//! use up_api::v1::money::Money;
 //! use up_api::v1::transactions::ListTransactionsOptions;
 //! 
 //! #[tokio::main]
@@ -17,23 +18,34 @@
 //!     let client = Client::new(token.to_string());
 //! 
 //!     let mut options = ListTransactionsOptions::default();
-//!     options.filter_since("2020-01-01T01:02:03Z".to_string());
+//!     options.filter_since("2020-01-01T01:02:03Z");
 //!     options.page_size(100);
 //! 
 //!     let transactions = client.list_transactions(&options).await.unwrap();
 //! 
-//!     let total : f32 =
-//!         transactions
-//!         .data
-//!         .into_iter()
-//!         .map(|t| t.attributes.amount.value)
-//!         .map(|v| v.parse::<f32>().unwrap())
-//!         .filter(|a| a > &0.0)
-//!         .sum();
+//!     let total =
+//!         Money::try_sum(
+//!             "AUD",
+//!             transactions
+//!             .data
+//!             .into_iter()
+//!             .map(|t| Money::from(t.attributes.amount))
+//!             .filter(|a| a.is_positive())
+//!         )
+//!         .unwrap();
 //! 
 //!     println!("{}", total);
 //! }
 //! ```
+//!
+//! ## Features
+//!
+//! - `chrono`: Adds `DateTime::to_chrono` for converting the date-times of resources (which are always parsed and ordered by instant) into `chrono::DateTime` values, along with `filter_since_chrono` and `filter_until_chrono` for setting the transaction filters from them.
+//! - `testing`: Provides the modules for testing code which uses a `Client` without a network: `v1::mock` (canned responses), `v1::cassette` (recording and replaying sessions), `v1::memory` (an in-memory implementation of the API) and `v1::generator` (realistic seeded data).
+//! - `test-server`: Provides `v1::test_server`, a local HTTP server implementing the API over in-memory data (enabling `testing` as well), which a `Client` can be pointed at with `ClientBuilder::base_url` for developing and testing offline.
+//! - `blocking`: Provides `v1::blocking::Client`, a synchronous client with the same methods as `Client` (including `next` and `prev` for following pages), for programs which do not otherwise use an async runtime. This still depends on Tokio, as each blocking client runs the async `Client` on its own single-threaded Tokio runtime, so its methods panic if called from within an async runtime.
+//! - `tracing`: Emits a `tracing` span for each request, recording the method, endpoint, status, latency, page number (when following pages of a list) and number of retries. Request and response bodies are emitted at the `TRACE` level with the access token and personal fields redacted.
+//! - `webhook-server`: Provides `v1::webhook_server`, an HTTP server which verifies the signature of each webhook event it receives and dispatches it to a `WebhookHandler`, responding with a status code which lets Up retry failed deliveries. Also provides `v1::event_store`, for skipping events which are delivered more than once.
 
 /// Module for interacting with the v1 (beta) release of the Up API.
 pub mod v1;
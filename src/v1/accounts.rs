@@ -1,5 +1,6 @@
-use crate::v1::{Client, error, BASE_URL, standard};
+use crate::v1::{Client, error, pagination, standard, validate_id};
 
+use futures::Stream;
 use serde::Deserialize;
 
 // ----------------- Response Objects -----------------
@@ -8,7 +9,7 @@ use serde::Deserialize;
 pub struct ListAccountsResponse {
     /// The list of accounts returned in this response.
     pub data : Vec<AccountResource>,
-    pub links : ResponseLinks,
+    pub links : pagination::PageLinks,
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,7 +23,7 @@ pub struct AccountResource {
     /// The type of this resource: `accounts`.
     pub r#type : String,
     /// The unique identifier for this account.
-    pub id : String,
+    pub id : standard::AccountId,
     pub attributes : Attributes,
     pub relationships : Relationships,
     pub links : Option<AccountResourceLinks>,
@@ -35,14 +36,6 @@ pub struct AccountResourceLinks {
     pub this : Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ResponseLinks {
-    /// The link to the previous page in the results. If this value is `None` there is no previous page.
-    pub prev : Option<String>,
-    /// The link to the next page in the results. If this value is `None` there is no next page.
-    pub next : Option<String>,
-}
-
 #[derive(Deserialize, Debug)]
 pub struct Relationships {
     pub transactions : Transactions,
@@ -71,7 +64,7 @@ pub struct Attributes {
     /// The available balance of the account, taking into account any amounts that are currently on hold.
     pub balance : standard::MoneyObject,
     /// The date-time at which this account was first opened.
-    pub created_at : String,
+    pub created_at : standard::DateTime,
 }
 
 // ----------------- Input Objects -----------------
@@ -81,53 +74,56 @@ pub struct ListAccountsOptions {
     /// The number of records to return in each page. 
     page_size : Option<u8>,
     /// The type of account for which to return records. This can be used to filter Savers from spending accounts.
-    filter_account_type : Option<String>,
+    filter_account_type : Option<standard::AccountTypeEnum>,
     /// The account ownership structure for which to return records. This can be used to filter 2Up accounts from Up accounts.
-    filter_ownership_type : Option<String>,
+    filter_ownership_type : Option<standard::OwnershipTypeEnum>,
 }
 
 impl ListAccountsOptions {
-    /// Sets the page size.
-    pub fn page_size(&mut self, value : u8) {
+    /// Sets the page size, which must be between 1 and 100.
+    pub fn page_size(&mut self, value : u8) -> &mut Self {
         self.page_size = Some(value);
+        self
     }
 
     /// Sets the account type filter value.
-    pub fn filter_account_type(&mut self, value : String) {
+    pub fn filter_account_type(&mut self, value : standard::AccountTypeEnum) -> &mut Self {
         self.filter_account_type = Some(value);
+        self
     }
 
     /// Sets the ownership type filter value.
-    pub fn filter_ownership_type(&mut self, value : String) {
+    pub fn filter_ownership_type(&mut self, value : standard::OwnershipTypeEnum) -> &mut Self {
         self.filter_ownership_type = Some(value);
+        self
+    }
+
+    /// Checks that the options are valid, as is done automatically before any request is made with them.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if let Some(value) = self.page_size {
+            pagination::validate_page_size(value)?;
+        }
+
+        Ok(())
     }
 
     fn add_params(&self, url : &mut reqwest::Url) {
-        let mut query = String::new();
+        let mut query = Vec::new();
 
         if let Some(value) = &self.page_size {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("page[size]={}", value));
+            query.push(("page[size]", value.to_string()));
         }
 
         if let Some(value) = &self.filter_account_type {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[accountType]={}", value));
+            query.push(("filter[accountType]", value.to_string()));
         }
 
         if let Some(value) = &self.filter_ownership_type {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[ownershipType]={}", value));
+            query.push(("filter[ownershipType]", value.to_string()));
         }
 
         if !query.is_empty() {
-            url.set_query(Some(&query));
+            url.query_pairs_mut().extend_pairs(query);
         }
     }
 }
@@ -135,66 +131,55 @@ impl ListAccountsOptions {
 impl Client {
     /// Retrieve a paginated list of all accounts for the currently authenticated user. The returned list is paginated and can be scrolled by following the `prev` and `next` links where present. 
     pub async fn list_accounts(&self, options : &ListAccountsOptions) -> Result<ListAccountsResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/accounts", BASE_URL)).map_err(error::Error::UrlParse)?;
+        options.validate()?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/accounts", self.base_url)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let account_response : ListAccountsResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let account_response : ListAccountsResponse = error::from_json(&res.body)?;
 
                 Ok(account_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
+    /// Retrieve every account for the currently authenticated user as a stream, following the `next` link of each page as it is needed. The `limits` can be used to cap the total number of accounts or pages retrieved.
+    pub fn list_accounts_stream<'a>(&'a self, options : &ListAccountsOptions, limits : pagination::StreamLimits) -> impl Stream<Item = Result<AccountResource, error::Error>> + 'a {
+        let url = options
+            .validate()
+            .and_then(|_| reqwest::Url::parse(&format!("{}/accounts", self.base_url)).map_err(error::Error::UrlParse))
+            .map(|mut url| {
+                options.add_params(&mut url);
+                url
+            });
+
+        pagination::stream::<ListAccountsResponse>(self, url, limits)
+    }
+
     /// Retrieve a specific account by providing its unique identifier.
-    pub async fn get_account(&self, id : &str) -> Result<GetAccountResponse, error::Error> {
-        // This assertion is because without an ID the request is thought to be a request for
-        // many accounts, and therefore the error messages are very unclear.
-        if id.is_empty() {
-            panic!("The provided account ID must not be empty.");
-        }
+    pub async fn get_account(&self, id : &standard::AccountId) -> Result<GetAccountResponse, error::Error> {
+        validate_id("account ID", id.as_str())?;
 
-        let url = reqwest::Url::parse(&format!("{}/accounts/{}", BASE_URL, id)).map_err(error::Error::UrlParse)?;
+        let url = reqwest::Url::parse(&format!("{}/accounts/{}", self.base_url, id)).map_err(error::Error::UrlParse)?;
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let account_response : GetAccountResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let account_response : GetAccountResponse = error::from_json(&res.body)?;
 
                 Ok(account_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 }
 
 // ----------------- Page Navigation -----------------
 
-implement_pagination_v1!(ListAccountsResponse);
+pagination::implement_paginated!(ListAccountsResponse, AccountResource);
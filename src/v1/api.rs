@@ -0,0 +1,148 @@
+use crate::v1::{Client, accounts, categories, error, standard, tags, transactions, utilities, webhooks};
+
+use async_trait::async_trait;
+
+/// The endpoints of the Up API, implemented by `Client` and by the in-memory `InMemoryApi`, such that code can be written against either (for example, taking `&dyn UpApi`) and tested without a network.
+///
+/// Each method behaves as the method of the same name on `Client`.
+#[async_trait]
+pub trait UpApi : Send + Sync {
+    /// Make a basic ping request to the API.
+    async fn ping(&self) -> Result<utilities::PingResponse, error::Error>;
+
+    /// Retrieve a paginated list of all accounts for the currently authenticated user.
+    async fn list_accounts(&self, options : &accounts::ListAccountsOptions) -> Result<accounts::ListAccountsResponse, error::Error>;
+
+    /// Retrieve a specific account by providing its unique identifier.
+    async fn get_account(&self, id : &standard::AccountId) -> Result<accounts::GetAccountResponse, error::Error>;
+
+    /// Retrieve a list of all transactions across all accounts for the currently authenticated user.
+    async fn list_transactions(&self, options : &transactions::ListTransactionsOptions) -> Result<transactions::ListTransactionsResponse, error::Error>;
+
+    /// Retrieve a list of all transactions for a specific account.
+    async fn list_transactions_by_account(&self, account_id : &standard::AccountId, options : &transactions::ListTransactionsOptions) -> Result<transactions::ListTransactionsResponse, error::Error>;
+
+    /// Retrieve a specific transaction by providing its unique identifier.
+    async fn get_transaction(&self, id : &standard::TransactionId) -> Result<transactions::GetTransactionResponse, error::Error>;
+
+    /// Retrieve a list of all categories and their ancestry.
+    async fn list_categories(&self, options : &categories::ListCategoriesOptions) -> Result<categories::ListCategoriesResponse, error::Error>;
+
+    /// Retrieve a specific category by providing its unique identifier.
+    async fn get_category(&self, id : &standard::CategoryId) -> Result<categories::GetCategoryResponse, error::Error>;
+
+    /// Updates the category associated with a transaction, or removes it when `category` is `None`.
+    async fn categorise_transaction(&self, transaction_id : &standard::TransactionId, category : Option<&standard::CategoryId>) -> Result<(), error::Error>;
+
+    /// Retrieve a list of all tags currently in use.
+    async fn list_tags(&self, options : &tags::ListTagsOptions) -> Result<tags::ListTagsResponse, error::Error>;
+
+    /// Associates one or more tags with a specific transaction.
+    async fn add_tags(&self, transaction_id : &standard::TransactionId, tags : Vec<standard::TagId>) -> Result<(), error::Error>;
+
+    /// Disassociates one or more tags from a specific transaction.
+    async fn delete_tags(&self, transaction_id : &standard::TransactionId, tags : Vec<standard::TagId>) -> Result<(), error::Error>;
+
+    /// Retrieve a list of configured webhooks.
+    async fn list_webhooks(&self, options : &webhooks::ListWebhooksOptions) -> Result<webhooks::ListWebhooksResponse, error::Error>;
+
+    /// Retrieve a specific webhook by providing its unique identifier.
+    async fn get_webhook(&self, id : &standard::WebhookId) -> Result<webhooks::GetWebhookResponse, error::Error>;
+
+    /// Create a new webhook with a given URL.
+    async fn create_webhook(&self, webhook_url : &str, description : Option<String>) -> Result<webhooks::CreateWebhookResponse, error::Error>;
+
+    /// Delete a specific webhook by providing its unique identifier.
+    async fn delete_webhook(&self, id : &standard::WebhookId) -> Result<(), error::Error>;
+
+    /// Send a `PING` event to a webhook by providing its unique identifier.
+    async fn ping_webhook(&self, id : &standard::WebhookId) -> Result<webhooks::PingWebhookResponse, error::Error>;
+
+    /// Retrieve a list of delivery logs for a webhook by providing its unique identifier.
+    async fn list_webhook_logs(&self, id : &standard::WebhookId, options : &webhooks::ListWebhookLogsOptions) -> Result<webhooks::ListWebhookLogsResponse, error::Error>;
+}
+
+/// Implements `UpApi` by calling the method of the same name on the `Client` returned by the given expression.
+macro_rules! delegate_up_api {
+    ($type:ty, |$self:ident| $client:expr) => {
+        #[async_trait::async_trait]
+        impl crate::v1::api::UpApi for $type {
+            async fn ping(&$self) -> Result<crate::v1::utilities::PingResponse, crate::v1::error::Error> {
+                crate::v1::Client::ping($client).await
+            }
+
+            async fn list_accounts(&$self, options : &crate::v1::accounts::ListAccountsOptions) -> Result<crate::v1::accounts::ListAccountsResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_accounts($client, options).await
+            }
+
+            async fn get_account(&$self, id : &crate::v1::standard::AccountId) -> Result<crate::v1::accounts::GetAccountResponse, crate::v1::error::Error> {
+                crate::v1::Client::get_account($client, id).await
+            }
+
+            async fn list_transactions(&$self, options : &crate::v1::transactions::ListTransactionsOptions) -> Result<crate::v1::transactions::ListTransactionsResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_transactions($client, options).await
+            }
+
+            async fn list_transactions_by_account(&$self, account_id : &crate::v1::standard::AccountId, options : &crate::v1::transactions::ListTransactionsOptions) -> Result<crate::v1::transactions::ListTransactionsResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_transactions_by_account($client, account_id, options).await
+            }
+
+            async fn get_transaction(&$self, id : &crate::v1::standard::TransactionId) -> Result<crate::v1::transactions::GetTransactionResponse, crate::v1::error::Error> {
+                crate::v1::Client::get_transaction($client, id).await
+            }
+
+            async fn list_categories(&$self, options : &crate::v1::categories::ListCategoriesOptions) -> Result<crate::v1::categories::ListCategoriesResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_categories($client, options).await
+            }
+
+            async fn get_category(&$self, id : &crate::v1::standard::CategoryId) -> Result<crate::v1::categories::GetCategoryResponse, crate::v1::error::Error> {
+                crate::v1::Client::get_category($client, id).await
+            }
+
+            async fn categorise_transaction(&$self, transaction_id : &crate::v1::standard::TransactionId, category : Option<&crate::v1::standard::CategoryId>) -> Result<(), crate::v1::error::Error> {
+                crate::v1::Client::categorise_transaction($client, transaction_id, category).await
+            }
+
+            async fn list_tags(&$self, options : &crate::v1::tags::ListTagsOptions) -> Result<crate::v1::tags::ListTagsResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_tags($client, options).await
+            }
+
+            async fn add_tags(&$self, transaction_id : &crate::v1::standard::TransactionId, tags : Vec<crate::v1::standard::TagId>) -> Result<(), crate::v1::error::Error> {
+                crate::v1::Client::add_tags($client, transaction_id, tags).await
+            }
+
+            async fn delete_tags(&$self, transaction_id : &crate::v1::standard::TransactionId, tags : Vec<crate::v1::standard::TagId>) -> Result<(), crate::v1::error::Error> {
+                crate::v1::Client::delete_tags($client, transaction_id, tags).await
+            }
+
+            async fn list_webhooks(&$self, options : &crate::v1::webhooks::ListWebhooksOptions) -> Result<crate::v1::webhooks::ListWebhooksResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_webhooks($client, options).await
+            }
+
+            async fn get_webhook(&$self, id : &crate::v1::standard::WebhookId) -> Result<crate::v1::webhooks::GetWebhookResponse, crate::v1::error::Error> {
+                crate::v1::Client::get_webhook($client, id).await
+            }
+
+            async fn create_webhook(&$self, webhook_url : &str, description : Option<String>) -> Result<crate::v1::webhooks::CreateWebhookResponse, crate::v1::error::Error> {
+                crate::v1::Client::create_webhook($client, webhook_url, description).await
+            }
+
+            async fn delete_webhook(&$self, id : &crate::v1::standard::WebhookId) -> Result<(), crate::v1::error::Error> {
+                crate::v1::Client::delete_webhook($client, id).await
+            }
+
+            async fn ping_webhook(&$self, id : &crate::v1::standard::WebhookId) -> Result<crate::v1::webhooks::PingWebhookResponse, crate::v1::error::Error> {
+                crate::v1::Client::ping_webhook($client, id).await
+            }
+
+            async fn list_webhook_logs(&$self, id : &crate::v1::standard::WebhookId, options : &crate::v1::webhooks::ListWebhookLogsOptions) -> Result<crate::v1::webhooks::ListWebhookLogsResponse, crate::v1::error::Error> {
+                crate::v1::Client::list_webhook_logs($client, id, options).await
+            }
+        }
+    };
+}
+
+#[cfg(any(test, feature = "testing"))]
+pub(crate) use delegate_up_api;
+
+delegate_up_api!(Client, |self| self);
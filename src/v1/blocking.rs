@@ -0,0 +1,252 @@
+use crate::v1::{accounts, categories, error, pagination, standard, tags, transactions, utilities, webhooks};
+
+use std::sync::Arc;
+
+use futures::{Stream, StreamExt};
+
+/// A synchronous client for the Up API, for programs which do not otherwise use async code.
+///
+/// This wraps the async `Client` (sharing its configuration, response types and errors) along with a single-threaded Tokio runtime on which each request is run to completion, so it depends on Tokio even though it does not expose it.
+///
+/// # Panics
+///
+/// Every method which makes a request panics if called from within an async runtime (such as inside `#[tokio::main]`), as the runtime of the client cannot be blocked on from there. Use the async `Client` in such programs instead.
+///
+/// ```no_run
+/// use up_api::v1::blocking::Client;
+/// use up_api::v1::accounts::ListAccountsOptions;
+///
+/// let client = Client::new(String::from("..."));
+///
+/// let mut page = client.list_accounts(&ListAccountsOptions::default()).unwrap();
+///
+/// loop {
+///     for account in &page.data {
+///         println!("{}", account.attributes.display_name);
+///     }
+///
+///     match client.next(&page) {
+///         Some(next) => page = next.unwrap(),
+///         None => break,
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Client {
+    inner : crate::v1::Client,
+    runtime : Arc<tokio::runtime::Runtime>,
+}
+
+impl Client {
+    /// Creates an instance of the `Client` from the access token. Visit [this page](https://api.up.com.au/getting_started) to get such a token.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime could not be created, as with `from_async`.
+    pub fn new(access_token : String) -> Self {
+        Self::from_async(crate::v1::Client::new(access_token))
+    }
+
+    /// Creates a blocking `Client` from an async `Client`, such as one configured with `ClientBuilder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime could not be created, as is also the case for `reqwest::blocking::Client`.
+    pub fn from_async(client : crate::v1::Client) -> Self {
+        let runtime =
+            tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to create the runtime for the blocking client.");
+
+        Client {
+            inner : client,
+            runtime : Arc::new(runtime),
+        }
+    }
+
+    /// The async `Client` which makes the requests.
+    pub fn inner(&self) -> &crate::v1::Client {
+        &self.inner
+    }
+
+    /// Converts a stream of resources into an iterator which retrieves each page as it is needed.
+    fn iter<'a, T : 'a>(&'a self, stream : impl Stream<Item = Result<T, error::Error>> + 'a) -> impl Iterator<Item = Result<T, error::Error>> + 'a {
+        let mut stream = Box::pin(stream);
+        std::iter::from_fn(move || self.runtime.block_on(stream.next()))
+    }
+
+    // ----------------- Page Navigation -----------------
+
+    /// Follows the link to the next page, returns None of the next page does not exist.
+    pub fn next<P : pagination::Paginated>(&self, page : &P) -> Option<Result<P, error::Error>> {
+        self.runtime.block_on(page.next(&self.inner))
+    }
+
+    /// Follows the link to the previous page, returns None of the previous page does not exist.
+    pub fn prev<P : pagination::Paginated>(&self, page : &P) -> Option<Result<P, error::Error>> {
+        self.runtime.block_on(page.prev(&self.inner))
+    }
+
+    // ----------------- Utilities -----------------
+
+    /// Make a basic ping request to the API. This is useful to verify that authentication is functioning correctly.
+    pub fn ping(&self) -> Result<utilities::PingResponse, error::Error> {
+        self.runtime.block_on(self.inner.ping())
+    }
+
+    // ----------------- Accounts -----------------
+
+    /// Retrieve a paginated list of all accounts for the currently authenticated user. The returned list is paginated and can be scrolled by following the `prev` and `next` links where present.
+    pub fn list_accounts(&self, options : &accounts::ListAccountsOptions) -> Result<accounts::ListAccountsResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_accounts(options))
+    }
+
+    /// Iterates over all accounts, retrieving further pages as needed, until the last page or the given limits are reached.
+    pub fn list_accounts_iter<'a>(&'a self, options : &accounts::ListAccountsOptions, limits : pagination::StreamLimits) -> impl Iterator<Item = Result<accounts::AccountResource, error::Error>> + 'a {
+        self.iter(self.inner.list_accounts_stream(options, limits))
+    }
+
+    /// Retrieve a specific account by providing its unique identifier.
+    pub fn get_account(&self, id : &standard::AccountId) -> Result<accounts::GetAccountResponse, error::Error> {
+        self.runtime.block_on(self.inner.get_account(id))
+    }
+
+    // ----------------- Transactions -----------------
+
+    /// Retrieve a list of all transactions across all accounts for the currently authenticated user. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present.
+    pub fn list_transactions(&self, options : &transactions::ListTransactionsOptions) -> Result<transactions::ListTransactionsResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_transactions(options))
+    }
+
+    /// Iterates over all transactions matching the options, retrieving further pages as needed, until the last page or the given limits are reached.
+    pub fn list_transactions_iter<'a>(&'a self, options : &transactions::ListTransactionsOptions, limits : pagination::StreamLimits) -> impl Iterator<Item = Result<transactions::TransactionResource, error::Error>> + 'a {
+        self.iter(self.inner.list_transactions_stream(options, limits))
+    }
+
+    /// Retrieve a specific transaction by providing its unique identifier.
+    pub fn get_transaction(&self, id : &standard::TransactionId) -> Result<transactions::GetTransactionResponse, error::Error> {
+        self.runtime.block_on(self.inner.get_transaction(id))
+    }
+
+    /// Retrieve a list of all transactions for a specific account. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present.
+    pub fn list_transactions_by_account(&self, account_id : &standard::AccountId, options : &transactions::ListTransactionsOptions) -> Result<transactions::ListTransactionsResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_transactions_by_account(account_id, options))
+    }
+
+    // ----------------- Categories -----------------
+
+    /// Retrieve a list of all categories and their ancestry. The returned list is not paginated.
+    pub fn list_categories(&self, options : &categories::ListCategoriesOptions) -> Result<categories::ListCategoriesResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_categories(options))
+    }
+
+    /// Retrieve a specific category by providing its unique identifier.
+    pub fn get_category(&self, id : &standard::CategoryId) -> Result<categories::GetCategoryResponse, error::Error> {
+        self.runtime.block_on(self.inner.get_category(id))
+    }
+
+    /// Updates the category associated with a transaction. Only transactions for which `is_categorizable` is set to true support this operation. The `category` is taken as `None` to remove the category.
+    pub fn categorise_transaction(&self, transaction_id : &standard::TransactionId, category : Option<&standard::CategoryId>) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.categorise_transaction(transaction_id, category))
+    }
+
+    // ----------------- Tags -----------------
+
+    /// Retrieve a list of all tags currently in use. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present.
+    pub fn list_tags(&self, options : &tags::ListTagsOptions) -> Result<tags::ListTagsResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_tags(options))
+    }
+
+    /// Iterates over all tags, retrieving further pages as needed, until the last page or the given limits are reached.
+    pub fn list_tags_iter<'a>(&'a self, options : &tags::ListTagsOptions, limits : pagination::StreamLimits) -> impl Iterator<Item = Result<tags::TagResource, error::Error>> + 'a {
+        self.iter(self.inner.list_tags_stream(options, limits))
+    }
+
+    /// Associates one or more tags with a specific transaction. No more than 6 tags may be present on any single transaction. Duplicate tags are silently ignored.
+    pub fn add_tags(&self, transaction_id : &standard::TransactionId, tags : Vec<standard::TagId>) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.add_tags(transaction_id, tags))
+    }
+
+    /// Disassociates one or more tags from a specific transaction. Tags that are not associated are silently ignored.
+    pub fn delete_tags(&self, transaction_id : &standard::TransactionId, tags : Vec<standard::TagId>) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.delete_tags(transaction_id, tags))
+    }
+
+    // ----------------- Webhooks -----------------
+
+    /// Retrieve a list of configured webhooks. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present.
+    pub fn list_webhooks(&self, options : &webhooks::ListWebhooksOptions) -> Result<webhooks::ListWebhooksResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_webhooks(options))
+    }
+
+    /// Iterates over all webhooks, retrieving further pages as needed, until the last page or the given limits are reached.
+    pub fn list_webhooks_iter<'a>(&'a self, options : &webhooks::ListWebhooksOptions, limits : pagination::StreamLimits) -> impl Iterator<Item = Result<webhooks::WebhookResource, error::Error>> + 'a {
+        self.iter(self.inner.list_webhooks_stream(options, limits))
+    }
+
+    /// Retrieve a specific webhook by providing its unique identifier.
+    pub fn get_webhook(&self, id : &standard::WebhookId) -> Result<webhooks::GetWebhookResponse, error::Error> {
+        self.runtime.block_on(self.inner.get_webhook(id))
+    }
+
+    /// Create a new webhook with a given URL. The URL will receive webhook events as JSON-encoded POST requests. The URL must respond with a HTTP 200 status on success.
+    pub fn create_webhook(&self, webhook_url : &str, description : Option<String>) -> Result<webhooks::CreateWebhookResponse, error::Error> {
+        self.runtime.block_on(self.inner.create_webhook(webhook_url, description))
+    }
+
+    /// Delete a specific webhook by providing its unique identifier. Once deleted, webhook events will no longer be sent to the configured URL.
+    pub fn delete_webhook(&self, id : &standard::WebhookId) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.delete_webhook(id))
+    }
+
+    /// Send a `PING` event to a webhook by providing its unique identifier. This is useful for testing and debugging purposes.
+    pub fn ping_webhook(&self, id : &standard::WebhookId) -> Result<webhooks::PingWebhookResponse, error::Error> {
+        self.runtime.block_on(self.inner.ping_webhook(id))
+    }
+
+    /// Retrieve a list of delivery logs for a webhook by providing its unique identifier. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present.
+    pub fn list_webhook_logs(&self, id : &standard::WebhookId, options : &webhooks::ListWebhookLogsOptions) -> Result<webhooks::ListWebhookLogsResponse, error::Error> {
+        self.runtime.block_on(self.inner.list_webhook_logs(id, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::memory::{MemoryTransport, Store};
+    use crate::v1::test_util;
+
+    use std::sync::Arc;
+
+    fn client() -> Client {
+        Client::from_async(test_util::client(Arc::new(MemoryTransport::new(Store::sample()))))
+    }
+
+    #[test]
+    fn pages_can_be_followed_synchronously() {
+        let client = client();
+
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.page_size(2);
+
+        let first = client.list_transactions(&options).unwrap();
+        let second = client.next(&first).unwrap().unwrap();
+        let third = client.next(&second).unwrap().unwrap();
+
+        assert_eq!(third.data.len(), 1);
+        assert!(client.next(&third).is_none());
+        assert_eq!(client.prev(&second).unwrap().unwrap().data[0].id, first.data[0].id);
+
+        let all : Vec<_> = client.list_transactions_iter(&options, pagination::StreamLimits::default().max_items(4)).collect::<Result<_, _>>().unwrap();
+        assert_eq!(all.len(), 4);
+    }
+
+    #[test]
+    fn errors_are_shared_with_the_async_client() {
+        let client = client();
+
+        assert!(client.get_account(&standard::AccountId::from("missing")).unwrap_err().is_not_found());
+        assert!(matches!(client.get_account(&standard::AccountId::from("")), Err(error::Error::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,416 @@
+use crate::v1::error;
+use crate::v1::scrub::{scrub_text, scrub_value};
+use crate::v1::transport::{Request, Response, Transport};
+
+pub use crate::v1::scrub::{DEFAULT_SCRUBBED_FIELDS, REDACTED};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Response headers which are never written to a cassette.
+const SCRUBBED_HEADERS : &[&str] = &["authorization", "cookie", "set-cookie"];
+
+/// A recorded session with the API, stored as JSON.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Cassette {
+    /// Every request made during the session and its response, in the order they were made.
+    pub interactions : Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    pub fn load(path : impl AsRef<Path>) -> Result<Self, error::Error> {
+        let path = path.as_ref();
+        let body = std::fs::read_to_string(path)
+            .map_err(|err| error::Error::Storage(format!("Failed to read the cassette {}: {}", path.display(), err)))?;
+
+        serde_json::from_str(&body).map_err(|err| error::Error::Storage(format!("Failed to parse the cassette {}: {}", path.display(), err)))
+    }
+
+    /// Writes the cassette to a JSON file, replacing the file if it exists.
+    pub fn save(&self, path : impl AsRef<Path>) -> Result<(), error::Error> {
+        let path = path.as_ref();
+        let body = serde_json::to_string_pretty(self).map_err(error::Error::Serialize)?;
+
+        std::fs::write(path, body)
+            .map_err(|err| error::Error::Storage(format!("Failed to write the cassette {}: {}", path.display(), err)))
+    }
+}
+
+/// A single request and the response it received.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Interaction {
+    pub request : RecordedRequest,
+    pub response : RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedRequest {
+    /// The HTTP method of the request.
+    pub method : String,
+    /// The full URL of the request, including any query parameters.
+    pub url : String,
+    /// The JSON body of the request, if it had one.
+    pub body : Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedResponse {
+    /// The HTTP status code of the response.
+    pub status : u16,
+    /// The headers of the response, other than those which may contain credentials.
+    pub headers : BTreeMap<String, String>,
+    /// The body of the response.
+    pub body : String,
+}
+
+// ----------------- Recording -----------------
+
+/// A `Transport` which sends requests through another transport (typically a `ReqwestTransport`) and records every request and response, to be written to a cassette with `save`.
+///
+/// The access token is never recorded, and the fields in `DEFAULT_SCRUBBED_FIELDS` (or those set with `scrubbed_fields`) are replaced with `REDACTED` wherever they appear in a JSON body.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use up_api::v1::Client;
+/// use up_api::v1::cassette::RecordingTransport;
+/// use up_api::v1::transport::ReqwestTransport;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let recorder = Arc::new(RecordingTransport::new(Arc::new(ReqwestTransport::default())));
+/// let client = Client::builder(std::env::var("UP_ACCESS_TOKEN").unwrap()).transport(recorder.clone()).build().unwrap();
+///
+/// client.ping().await.unwrap();
+/// recorder.save("tests/cassettes/ping.json").unwrap();
+/// # }
+/// ```
+pub struct RecordingTransport {
+    inner : Arc<dyn Transport>,
+    scrubbed_fields : Vec<String>,
+    interactions : Mutex<Vec<Interaction>>,
+}
+
+impl RecordingTransport {
+    /// Creates a transport which records the requests sent through `inner`.
+    pub fn new(inner : Arc<dyn Transport>) -> Self {
+        RecordingTransport {
+            inner,
+            scrubbed_fields : DEFAULT_SCRUBBED_FIELDS.iter().map(|f| String::from(*f)).collect(),
+            interactions : Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Sets the JSON fields which are scrubbed from bodies, replacing `DEFAULT_SCRUBBED_FIELDS`.
+    pub fn scrubbed_fields(mut self, fields : &[&str]) -> Self {
+        self.scrubbed_fields = fields.iter().map(|f| String::from(*f)).collect();
+        self
+    }
+
+    /// The session recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        Cassette {
+            interactions : self.interactions.lock().unwrap().clone(),
+        }
+    }
+
+    /// Writes the session recorded so far to a JSON file.
+    pub fn save(&self, path : impl AsRef<Path>) -> Result<(), error::Error> {
+        self.cassette().save(path)
+    }
+
+    fn scrub(&self, text : &str, token : Option<&str>) -> String {
+        scrub_text(text, token, &self.scrubbed_fields)
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn send(&self, request : Request) -> Result<Response, error::Error> {
+        let token =
+            request.headers
+            .get(reqwest::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| String::from(value.trim_start_matches("Bearer ")));
+
+        let recorded_request = RecordedRequest {
+            method : request.method.to_string(),
+            url : self.scrub(request.url.as_str(), token.as_deref()),
+            body : request.body.as_deref().map(|body| self.scrub(body, token.as_deref())),
+        };
+
+        let response = self.inner.send(request).await?;
+
+        let headers =
+            response.headers
+            .iter()
+            .filter(|(name, _)| !SCRUBBED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((String::from(name.as_str()), String::from(value.to_str().ok()?))))
+            .collect();
+
+        let recorded_response = RecordedResponse {
+            status : response.status.as_u16(),
+            headers,
+            body : self.scrub(&response.body, token.as_deref()),
+        };
+
+        self.interactions.lock().unwrap().push(Interaction {
+            request : recorded_request,
+            response : recorded_response,
+        });
+
+        Ok(response)
+    }
+}
+
+// ----------------- Replaying -----------------
+
+/// A `Transport` which replays a recorded cassette without a network.
+///
+/// Each request is matched against the first unused interaction with the same method, path, query parameters (in any order) and body, so that a session is replayed exactly as it was recorded, including any retries and pages followed through `next` and `prev`. The host of the URL is ignored, so a `Client` with a different `base_url` can replay the cassette. A request with no matching interaction fails with `Error::Transport`, while a cassette which cannot be read or holds an invalid status code fails with `Error::Storage`.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    source : Option<PathBuf>,
+    scrubbed_fields : Vec<String>,
+    interactions : Vec<Interaction>,
+    used : Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    /// Creates a transport which replays the given cassette.
+    pub fn new(cassette : Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+
+        ReplayTransport {
+            source : None,
+            scrubbed_fields : DEFAULT_SCRUBBED_FIELDS.iter().map(|f| String::from(*f)).collect(),
+            interactions : cassette.interactions,
+            used : Mutex::new(used),
+        }
+    }
+
+    /// Creates a transport which replays the cassette in the given JSON file.
+    pub fn load(path : impl AsRef<Path>) -> Result<Self, error::Error> {
+        let mut transport = Self::new(Cassette::load(path.as_ref())?);
+        transport.source = Some(path.as_ref().to_path_buf());
+        Ok(transport)
+    }
+
+    /// Sets the JSON fields which were scrubbed from bodies when the cassette was recorded, so that request bodies can still be matched. This must be the same as was set with `RecordingTransport::scrubbed_fields`.
+    pub fn scrubbed_fields(mut self, fields : &[&str]) -> Self {
+        self.scrubbed_fields = fields.iter().map(|f| String::from(*f)).collect();
+        self
+    }
+
+    /// The interactions of the cassette which have not yet been replayed.
+    pub fn remaining(&self) -> Vec<Interaction> {
+        let used = self.used.lock().unwrap();
+
+        self.interactions
+            .iter()
+            .zip(used.iter())
+            .filter(|(_, used)| !**used)
+            .map(|(interaction, _)| interaction.clone())
+            .collect()
+    }
+
+    /// Panics unless every interaction in the cassette has been replayed.
+    pub fn assert_finished(&self) {
+        let remaining : Vec<String> = self.remaining().iter().map(|i| format!("{} {}", i.request.method, i.request.url)).collect();
+
+        if !remaining.is_empty() {
+            panic!("{} recorded interactions were never replayed: {:?}", remaining.len(), remaining);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request : Request) -> Result<Response, error::Error> {
+        let mut used = self.used.lock().unwrap();
+
+        let index =
+            (0..self.interactions.len())
+            .find(|&i| !used[i] && matches(&self.interactions[i].request, &request, &self.scrubbed_fields));
+
+        let index = match index {
+            Some(index) => index,
+            None => {
+                let source = self.source.as_ref().map(|s| format!(" in {}", s.display())).unwrap_or_default();
+                return Err(error::Error::Transport(format!("No unused recorded interaction{} matches {} {}", source, request.method, request.url)));
+            },
+        };
+
+        used[index] = true;
+
+        let recorded = &self.interactions[index].response;
+
+        let status = reqwest::StatusCode::from_u16(recorded.status)
+            .map_err(|_| error::Error::Storage(format!("The recorded status code {} is invalid", recorded.status)))?;
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &recorded.headers {
+            if let (Ok(name), Ok(value)) = (reqwest::header::HeaderName::from_bytes(name.as_bytes()), reqwest::header::HeaderValue::from_str(value)) {
+                headers.insert(name, value);
+            }
+        }
+
+        Ok(Response {
+            status,
+            headers,
+            body : recorded.body.clone(),
+        })
+    }
+}
+
+/// Whether a recorded request is the same as a request being made, ignoring the host and the order of query parameters.
+fn matches(recorded : &RecordedRequest, request : &Request, scrubbed_fields : &[String]) -> bool {
+    let url = match reqwest::Url::parse(&recorded.url) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+
+    let mut recorded_query : Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let mut query : Vec<(String, String)> = request.url.query_pairs().into_owned().collect();
+    recorded_query.sort();
+    query.sort();
+
+    recorded.method == request.method.as_str()
+        && url.path() == request.url.path()
+        && recorded_query == query
+        && same_body(recorded.body.as_deref(), request.body.as_deref(), scrubbed_fields)
+}
+
+/// Whether two bodies are the same, comparing them as JSON where possible after scrubbing the body being sent in the same way as the recorded body.
+fn same_body(recorded : Option<&str>, body : Option<&str>, scrubbed_fields : &[String]) -> bool {
+    match (recorded, body) {
+        (None, None) => true,
+        (Some(recorded), Some(body)) => {
+            match (serde_json::from_str::<serde_json::Value>(recorded), serde_json::from_str::<serde_json::Value>(body)) {
+                (Ok(recorded), Ok(mut body)) => {
+                    scrub_value(&mut body, scrubbed_fields);
+                    recorded == body
+                },
+                _ => recorded == body,
+            }
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::mock::MockTransport;
+    use crate::v1::test_util::{TOKEN, client};
+
+    const PING : &str = r#"{"meta":{"id":"abc","statusEmoji":"⚡️"}}"#;
+
+    const WEBHOOK : &str = r#"{
+        "data": {
+            "type": "webhooks",
+            "id": "hook",
+            "attributes": {
+                "url": "https://example.com/private/hook",
+                "description": "Jane's webhook",
+                "secretKey": "shhh",
+                "createdAt": "2022-01-01T00:00:00+10:00"
+            },
+            "relationships": { "logs": { "links": { "related": "https://api.up.com.au/api/v1/webhooks/hook/logs" } } },
+            "links": { "self": "https://api.up.com.au/api/v1/webhooks/hook" }
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn recorded_sessions_are_scrubbed_and_replayed() {
+        let mock = Arc::new(MockTransport::new());
+        mock.register(reqwest::Method::GET, "/util/ping", Response::json(reqwest::StatusCode::OK, PING));
+        mock.register(reqwest::Method::POST, "/webhooks", Response::json(reqwest::StatusCode::CREATED, WEBHOOK));
+
+        let recorder = Arc::new(RecordingTransport::new(mock));
+        let live = client(recorder.clone());
+
+        live.ping().await.unwrap();
+        live.create_webhook("https://example.com/private/hook", Some(String::from("Jane's webhook"))).await.unwrap();
+
+        let path = std::env::temp_dir().join(format!("up-api-cassette-{}.json", std::process::id()));
+        recorder.save(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+
+        assert!(!contents.contains(TOKEN));
+        assert!(!contents.contains("shhh"));
+        assert!(!contents.contains("Jane"));
+        assert!(!contents.contains("example.com"));
+
+        let replay = Arc::new(ReplayTransport::load(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let offline = client(replay.clone());
+        assert_eq!(offline.ping().await.unwrap().meta.id, "abc");
+
+        // The body is matched after scrubbing, so a different URL and description match as well.
+        let webhook = offline.create_webhook("https://example.com/other", Some(String::from("Another webhook"))).await.unwrap();
+        assert_eq!(webhook.data.attributes.secret_key.as_deref(), Some(REDACTED));
+
+        replay.assert_finished();
+    }
+
+    #[tokio::test]
+    async fn unmatched_requests_fail() {
+        let replay = Arc::new(ReplayTransport::new(Cassette {
+            interactions : vec![Interaction {
+                request : RecordedRequest {
+                    method : String::from("GET"),
+                    url : String::from("https://api.up.com.au/api/v1/util/ping"),
+                    body : None,
+                },
+                response : RecordedResponse {
+                    status : 200,
+                    headers : BTreeMap::new(),
+                    body : String::from(PING),
+                },
+            }],
+        }));
+
+        let client = client(replay.clone());
+
+        assert!(client.ping().await.is_ok());
+        assert!(matches!(client.ping().await, Err(error::Error::Transport(_))));
+        assert!(replay.remaining().is_empty());
+    }
+
+    #[tokio::test]
+    async fn broken_cassettes_are_storage_errors() {
+        let path = std::env::temp_dir().join(format!("up-api-broken-cassette-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(Cassette::load(&path), Err(error::Error::Storage(_))));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(Cassette::load(&path), Err(error::Error::Storage(_))));
+        std::fs::remove_file(&path).unwrap();
+
+        let missing = std::env::temp_dir().join(format!("up-api-missing-{}", std::process::id())).join("cassette.json");
+        assert!(matches!(Cassette { interactions : Vec::new() }.save(&missing), Err(error::Error::Storage(_))));
+
+        let replay = Arc::new(ReplayTransport::new(Cassette {
+            interactions : vec![Interaction {
+                request : RecordedRequest {
+                    method : String::from("GET"),
+                    url : String::from("https://api.up.com.au/api/v1/util/ping"),
+                    body : None,
+                },
+                response : RecordedResponse {
+                    status : 1000,
+                    headers : BTreeMap::new(),
+                    body : String::from(PING),
+                },
+            }],
+        }));
+
+        assert!(matches!(client(replay).ping().await, Err(error::Error::Storage(_))));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::v1::{Client, error, BASE_URL};
+use crate::v1::{Client, error, standard, validate_id};
 
 use serde::{Deserialize, Serialize};
 
@@ -22,7 +22,7 @@ pub struct CategoryResource {
     /// The type of this resource: categories
     pub r#type : String,
     /// The unique identifier for this category. This is a human-readable but URL-safe value.
-    pub id : String,
+    pub id : standard::CategoryId,
     pub attributes : Attributes,
     pub relationships : Relationships,
     pub links : Option<CategoryResourceLinks>,
@@ -51,7 +51,7 @@ pub struct ParentData {
     /// The type of this resource: `categories`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::CategoryId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -71,7 +71,7 @@ pub struct ChildrenData {
     /// The type of this resource: `categories`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::CategoryId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -92,27 +92,25 @@ pub struct CategoryResourceLinks {
 #[derive(Default)]
 pub struct ListCategoriesOptions {
     /// The unique identifier of a parent category for which to return only its children.
-    filter_parent : Option<String>,
+    filter_parent : Option<standard::CategoryId>,
 }
 
 impl ListCategoriesOptions {
     /// Sets the parent filter value.
-    pub fn filter_parent(&mut self, value : String) {
+    pub fn filter_parent(&mut self, value : standard::CategoryId) -> &mut Self {
         self.filter_parent = Some(value);
+        self
     }
 
     fn add_params(&self, url : &mut reqwest::Url) {
-        let mut query = String::new();
+        let mut query = Vec::new();
 
         if let Some(value) = &self.filter_parent {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[parent]={}", value));
+            query.push(("filter[parent]", value.to_string()));
         }
 
         if !query.is_empty() {
-            url.set_query(Some(&query));
+            url.query_pairs_mut().extend_pairs(query);
         }
     }
 }
@@ -130,106 +128,72 @@ struct CategoryInputResourceIdentifier {
     /// The type of this resource: `categories`
     r#type : String,
     /// The unique identifier of the category, as returned by the `list_categories` method.
-    id : String,
+    id : standard::CategoryId,
 }
 
 impl Client {
     /// Retrieve a list of all categories and their ancestry. The returned list is not paginated.
     pub async fn list_categories(&self, options : &ListCategoriesOptions) -> Result<ListCategoriesResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/categories", BASE_URL)).map_err(error::Error::UrlParse)?;
+        let mut url = reqwest::Url::parse(&format!("{}/categories", self.base_url)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let category_response : ListCategoriesResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let category_response : ListCategoriesResponse = error::from_json(&res.body)?;
 
                 Ok(category_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Retrieve a specific category by providing its unique identifier.
-    pub async fn get_category(&self, id : &str) -> Result<GetCategoryResponse, error::Error> {
-        // This assertion is because without an ID the request is thought to be a request for
-        // many categories, and therefore the error messages are very unclear.
-        if id.is_empty() {
-            panic!("The provided category ID must not be empty.");
-        }
+    pub async fn get_category(&self, id : &standard::CategoryId) -> Result<GetCategoryResponse, error::Error> {
+        validate_id("category ID", id.as_str())?;
 
-        let url = reqwest::Url::parse(&format!("{}/categories/{}", BASE_URL, id)).map_err(error::Error::UrlParse)?;
+        let url = reqwest::Url::parse(&format!("{}/categories/{}", self.base_url, id)).map_err(error::Error::UrlParse)?;
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let category_response : GetCategoryResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let category_response : GetCategoryResponse = error::from_json(&res.body)?;
 
                 Ok(category_response )
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Updates the category associated with a transaction. Only transactions for which `is_categorizable` is set to true support this operation. The `id` is taken from the list exposed on `list_categories` and cannot be one of the top-level (parent) categories. To de-categorize a transaction, set the entire `data` key to `null`. The associated category, along with its request URL is also exposed via the category relationship on the transaction resource returned from `get_transaction`.
-    pub async fn categorise_transaction(&self, transaction_id : &str, category : Option<&str>) -> Result<(), error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/transactions/{}/relationships/category", BASE_URL, transaction_id)).map_err(error::Error::UrlParse)?;
+    pub async fn categorise_transaction(&self, transaction_id : &standard::TransactionId, category : Option<&standard::CategoryId>) -> Result<(), error::Error> {
+        validate_id("transaction ID", transaction_id.as_str())?;
+
+        if let Some(category) = category {
+            validate_id("category ID", category.as_str())?;
+        }
+
+        let url = reqwest::Url::parse(&format!("{}/transactions/{}/relationships/category", self.base_url, transaction_id)).map_err(error::Error::UrlParse)?;
 
         let category = category.map(|id| {
             CategoryInputResourceIdentifier {
                 r#type : String::from("categories"),
-                id : String::from(id),
+                id : id.clone(),
             }
         });
 
         let body = CategoriseTransactionRequest  { data : category };
         let body = serde_json::to_string(&body).map_err(error::Error::Serialize)?;
 
-        println!("{}", body);
+        let res = self.send(reqwest::Method::PATCH, url, Some(body)).await?;
 
-        let res = reqwest::Client::new()
-            .patch(url)
-            .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
-
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::NO_CONTENT => {
                 Ok(())
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 }
@@ -1,6 +1,10 @@
 use std::fmt;
 
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+/// The maximum number of characters of a response body kept in an error.
+const MAX_BODY_EXCERPT : usize = 1024;
 
 #[derive(Debug)]
 /// Primary error type for requests made through `up_api::Client`.
@@ -10,16 +14,34 @@ pub enum Error {
     /// Represents an error in making the HTTP request.
     Request(reqwest::Error),
     /// Represents errors from the API (i.e. a non `2XX` response code).
-    Api(ErrorResponse),
-    /// Represents an error in deserializing JSON to the required structures. Occurances of this
-    /// error should be treated as a bug in the library.
-    Json(serde_json::Error),
+    Api(Box<ApiError>),
+    /// Represents an unexpected response whose body is not a JSON:API error document, such as an
+    /// HTML page returned by a proxy or an empty body.
+    UnexpectedResponse(Box<UnexpectedResponse>),
+    /// Represents an error in deserializing JSON to the required structures, along with a
+    /// (possibly truncated) copy of the body. Occurances of this error should be treated as a bug
+    /// in the library.
+    Json(serde_json::Error, String),
     /// Represents an error in reading the body from the HTTP response. Occurances of this
     /// error should be treated as a bug in the library. 
     BodyRead(reqwest::Error),
     /// Represents an error serializing the data to be sent to the API. Occurances of this
     /// error should be treated as a bug in the library.
     Serialize(serde_json::Error),
+    /// Represents options for a list request which are invalid (such as an out of range page
+    /// size), detected before any request is sent.
+    InvalidOptions(String),
+    /// Represents an argument to a request which is invalid (such as an empty ID), detected
+    /// before any request is sent.
+    InvalidArgument(String),
+    /// Represents a failure of a `Transport` other than the default, such as a `MockTransport`
+    /// with no response registered for the request.
+    Transport(String),
+    /// Represents a webhook request whose `X-Up-Authenticity-Signature` header could not be
+    /// verified against the webhook's secret key, such that its body cannot be trusted.
+    Verification(VerificationError),
+    /// Represents a failure to read or write stored data, such as the file of an `EventStore` or a cassette.
+    Storage(String),
 }
 
 impl fmt::Display for Error {
@@ -27,16 +49,148 @@ impl fmt::Display for Error {
         match self {
             Self::UrlParse(val) => write!(f, "Failed to parse the URL before making the request: {:?}", val),
             Self::Request(val) => write!(f, "Failed to make the HTTP request to the API endpoint: {:?}", val),
-            Self::Api(val) => write!(f, "The API returned an error response: {:?}", val),
-            Self::Json(val) => write!(f, "Failed to deserialize the returned JSON to the correct format: {:?}", val),
+            Self::Api(val) => write!(f, "The API returned an error response with status {}: {:?}", val.status, val.response),
+            Self::UnexpectedResponse(val) => write!(f, "The API returned an unexpected response with status {}: {:?}", val.status, val.body),
+            Self::Json(val, body) => write!(f, "Failed to deserialize the returned JSON to the correct format: {:?} in {:?}", val, body),
             Self::BodyRead(val) => write!(f, "Failed to read the response body as a UTF-8 string: {:?}", val),
             Self::Serialize(val) => write!(f, "Failed to serialize the request data: {:?}", val),
+            Self::InvalidOptions(val) => write!(f, "The provided options are invalid: {}", val),
+            Self::InvalidArgument(val) => write!(f, "The provided argument is invalid: {}", val),
+            Self::Transport(val) => write!(f, "The transport failed to send the request: {}", val),
+            Self::Verification(val) => write!(f, "Failed to verify the webhook signature: {}", val),
+            Self::Storage(val) => write!(f, "Failed to access storage: {}", val),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// The HTTP status code of the response which caused this error, if a response was received.
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Api(val) => Some(val.status),
+            Self::UnexpectedResponse(val) => Some(val.status),
+            Self::Request(val) => val.status(),
+            _ => None,
+        }
+    }
+
+    /// The errors returned by the API, which is empty unless this is an `Api` error.
+    pub fn api_errors(&self) -> &[ErrorObject] {
+        match self {
+            Self::Api(val) => &val.response.errors,
+            _ => &[],
+        }
+    }
+
+    /// Whether the request may succeed if it is made again, as is the case for rate limiting, server errors and connection problems.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request(val) => crate::v1::retry::is_retryable_error(val),
+            _ => self.status().is_some_and(crate::v1::retry::is_retryable_status),
+        }
+    }
+
+    /// Whether the access token was missing, invalid or revoked (a `401` response).
+    pub fn is_unauthorized(&self) -> bool {
+        self.status() == Some(reqwest::StatusCode::UNAUTHORIZED)
+    }
+
+    /// Whether the requested resource does not exist (a `404` response).
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(reqwest::StatusCode::NOT_FOUND)
+    }
+
+    /// Whether the request was rejected due to rate limiting (a `429` response).
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    /// Whether the request was invalid, either as detected before it was sent or as reported by the API (a `400` or `422` response). The `source` of each of the `api_errors` indicates which part of the request was rejected.
+    pub fn is_validation(&self) -> bool {
+        match self {
+            Self::InvalidOptions(_) | Self::InvalidArgument(_) => true,
+            _ => matches!(self.status(), Some(reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNPROCESSABLE_ENTITY)),
+        }
+    }
+
+    /// Whether this is a webhook request which failed signature verification, and so should be rejected.
+    pub fn is_verification(&self) -> bool {
+        matches!(self, Self::Verification(_))
+    }
+
+    /// Converts a response with an unexpected status code into the appropriate error, depending
+    /// on whether the body is a JSON:API error document.
+    pub(crate) fn from_response(res : crate::v1::transport::Response) -> Self {
+        let crate::v1::transport::Response { status, headers, body } = res;
+
+        match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(response) if !response.errors.is_empty() => Self::Api(Box::new(ApiError { status, headers, response })),
+            _ => Self::UnexpectedResponse(Box::new(UnexpectedResponse { status, headers, body })),
+        }
+    }
+}
+
+/// Deserializes a response body, keeping an excerpt of the body in the error if this fails.
+pub(crate) fn from_json<T : DeserializeOwned>(body : &str) -> Result<T, Error> {
+    serde_json::from_str(body).map_err(|err| Error::Json(err, excerpt(body)))
+}
+
+/// Deserializes a raw body which may not be valid UTF-8, keeping an excerpt of the body in the error if this fails.
+pub(crate) fn from_json_bytes<T : DeserializeOwned>(body : &[u8]) -> Result<T, Error> {
+    serde_json::from_slice(body).map_err(|err| Error::Json(err, excerpt(&String::from_utf8_lossy(body))))
+}
+
+/// Truncates a body to at most `MAX_BODY_EXCERPT` characters.
+fn excerpt(body : &str) -> String {
+    match body.char_indices().nth(MAX_BODY_EXCERPT) {
+        Some((index, _)) => format!("{}...", &body[..index]),
+        None => String::from(body),
+    }
+}
+
+/// The reason a webhook signature could not be verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The request had no `X-Up-Authenticity-Signature` header.
+    MissingSignature,
+    /// The signature was not a hex encoded SHA-256 HMAC.
+    MalformedSignature,
+    /// The signature did not match the body, so the body was not sent by Up or was signed with another webhook's secret key.
+    InvalidSignature,
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::MissingSignature => write!(f, "The request has no signature."),
+            Self::MalformedSignature => write!(f, "The signature is not a hex encoded SHA-256 HMAC."),
+            Self::InvalidSignature => write!(f, "The signature does not match the request body."),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiError {
+    /// The HTTP status code of the response.
+    pub status : reqwest::StatusCode,
+    /// The headers of the response.
+    pub headers : reqwest::header::HeaderMap,
+    /// The errors returned in the body of the response.
+    pub response : ErrorResponse,
+}
+
+#[derive(Debug)]
+pub struct UnexpectedResponse {
+    /// The HTTP status code of the response.
+    pub status : reqwest::StatusCode,
+    /// The headers of the response.
+    pub headers : reqwest::header::HeaderMap,
+    /// The raw body of the response.
+    pub body : String,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ErrorResponse {
     /// The list of errors returned in this response.
@@ -55,6 +209,13 @@ pub struct ErrorObject {
     pub source : Option<Source>,
 }
 
+impl ErrorObject {
+    /// The HTTP status code associated with this error, if the `status` is a valid code.
+    pub fn status_code(&self) -> Option<reqwest::StatusCode> {
+        self.status.parse::<u16>().ok().and_then(|code| reqwest::StatusCode::from_u16(code).ok())
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Source {
     /// If this error relates to a query parameter, the name of the parameter.
@@ -62,3 +223,151 @@ pub struct Source {
     /// If this error relates to an attribute in the request body, a rfc-6901 JSON pointer to the attribute.
     pub pointer : Option<String>
 }
+
+impl Source {
+    /// A typed view of the location in the request that this error relates to.
+    pub fn location(&self) -> Option<SourceLocation> {
+        if let Some(parameter) = &self.parameter {
+            return Some(SourceLocation::Parameter(QueryParameter::from(parameter.as_str())));
+        }
+
+        self.pointer.as_ref().map(|pointer| {
+            let tokens =
+                pointer
+                .split('/')
+                .skip(1)
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .collect();
+
+            SourceLocation::Pointer(tokens)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The location in a request that an error relates to.
+pub enum SourceLocation {
+    /// A parameter in the query string.
+    Parameter(QueryParameter),
+    /// An attribute in the request body, given as the reference tokens of its JSON pointer (for example `/data/0/id` is `["data", "0", "id"]`).
+    Pointer(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A query parameter which may be set through the options of a list request.
+pub enum QueryParameter {
+    /// `page[size]`, set with `page_size`.
+    PageSize,
+    /// `filter[status]`, set with `ListTransactionsOptions::filter_status`.
+    FilterStatus,
+    /// `filter[since]`, set with `ListTransactionsOptions::filter_since`.
+    FilterSince,
+    /// `filter[until]`, set with `ListTransactionsOptions::filter_until`.
+    FilterUntil,
+    /// `filter[category]`, set with `ListTransactionsOptions::filter_category`.
+    FilterCategory,
+    /// `filter[tag]`, set with `ListTransactionsOptions::filter_tag`.
+    FilterTag,
+    /// `filter[accountType]`, set with `ListAccountsOptions::filter_account_type`.
+    FilterAccountType,
+    /// `filter[ownershipType]`, set with `ListAccountsOptions::filter_ownership_type`.
+    FilterOwnershipType,
+    /// `filter[parent]`, set with `ListCategoriesOptions::filter_parent`.
+    FilterParent,
+    /// Any other parameter, such as the `page[before]` and `page[after]` cursors of pagination links.
+    Other(String),
+}
+
+impl From<&str> for QueryParameter {
+    fn from(value : &str) -> Self {
+        match value {
+            "page[size]" => Self::PageSize,
+            "filter[status]" => Self::FilterStatus,
+            "filter[since]" => Self::FilterSince,
+            "filter[until]" => Self::FilterUntil,
+            "filter[category]" => Self::FilterCategory,
+            "filter[tag]" => Self::FilterTag,
+            "filter[accountType]" => Self::FilterAccountType,
+            "filter[ownershipType]" => Self::FilterOwnershipType,
+            "filter[parent]" => Self::FilterParent,
+            other => Self::Other(String::from(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_errors_keep_an_excerpt_of_the_body() {
+        let result : Result<ErrorResponse, Error> = from_json("<html>502 Bad Gateway</html>");
+
+        match result {
+            Err(Error::Json(_, body)) => assert_eq!(body, "<html>502 Bad Gateway</html>"),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn sources_are_typed() {
+        let response : ErrorResponse = from_json(r#"{
+            "errors": [
+                {
+                    "status": "400",
+                    "title": "Invalid Parameter",
+                    "detail": "The filter[since] parameter is not a valid date-time.",
+                    "source": { "parameter": "filter[since]" }
+                },
+                {
+                    "status": "422",
+                    "title": "Invalid Attribute",
+                    "detail": "The tag is too long.",
+                    "source": { "pointer": "/data/0/id~1label" }
+                }
+            ]
+        }"#).unwrap();
+
+        let locations : Vec<Option<SourceLocation>> = response.errors.iter().map(|e| e.source.as_ref().and_then(Source::location)).collect();
+
+        assert_eq!(locations, vec![
+            Some(SourceLocation::Parameter(QueryParameter::FilterSince)),
+            Some(SourceLocation::Pointer(vec![String::from("data"), String::from("0"), String::from("id/label")])),
+        ]);
+        assert_eq!(response.errors[1].status_code(), Some(reqwest::StatusCode::UNPROCESSABLE_ENTITY));
+
+        let error = Error::Api(Box::new(ApiError {
+            status : reqwest::StatusCode::BAD_REQUEST,
+            headers : reqwest::header::HeaderMap::new(),
+            response,
+        }));
+
+        assert!(error.is_validation());
+        assert!(!error.is_retryable());
+        assert!(!error.is_not_found());
+        assert_eq!(error.api_errors().len(), 2);
+    }
+
+    #[test]
+    fn unexpected_responses_are_classified_by_status() {
+        let error = Error::UnexpectedResponse(Box::new(UnexpectedResponse {
+            status : reqwest::StatusCode::BAD_GATEWAY,
+            headers : reqwest::header::HeaderMap::new(),
+            body : String::from("<html>502 Bad Gateway</html>"),
+        }));
+
+        assert!(error.is_retryable());
+        assert!(!error.is_rate_limited());
+        assert!(!error.is_unauthorized());
+        assert!(error.api_errors().is_empty());
+    }
+
+    #[test]
+    fn long_bodies_are_truncated() {
+        let body = "é".repeat(MAX_BODY_EXCERPT + 10);
+        let excerpt = excerpt(&body);
+
+        assert_eq!(excerpt.chars().count(), MAX_BODY_EXCERPT + 3);
+        assert!(excerpt.ends_with("..."));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::v1::error;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+
+/// Records the IDs of webhook events which have been processed, such that a `Dispatcher` can skip events which Up delivers more than once.
+///
+/// Each ID only needs to be remembered for as long as Up may retry its delivery, after which an implementation may forget it.
+///
+/// Skipping events is best-effort: an event is only recorded once it has been handled, so a redelivery which arrives while the first delivery is still being handled is not skipped. Handlers should still be idempotent where handling an event twice matters.
+#[async_trait]
+pub trait EventStore : Send + Sync {
+    /// Whether the event with the given ID has already been processed.
+    async fn contains(&self, event_id : &str) -> Result<bool, error::Error>;
+
+    /// Records that the event with the given ID has been processed.
+    async fn insert(&self, event_id : &str) -> Result<(), error::Error>;
+}
+
+/// The IDs of processed events along with when they were processed, forgetting those older than the retention window.
+#[derive(Clone, Debug)]
+struct Entries {
+    processed : HashMap<String, SystemTime>,
+    retention : Duration,
+}
+
+impl Entries {
+    fn new(retention : Duration) -> Self {
+        Entries {
+            processed : HashMap::new(),
+            retention,
+        }
+    }
+
+    fn contains(&self, event_id : &str, now : SystemTime) -> bool {
+        self.processed.get(event_id).is_some_and(|processed_at| is_current(*processed_at, now, self.retention))
+    }
+
+    /// Records the event, forgetting any which have expired.
+    fn insert(&mut self, event_id : &str, now : SystemTime) {
+        let retention = self.retention;
+        self.processed.retain(|_, processed_at| is_current(*processed_at, now, retention));
+        self.processed.insert(String::from(event_id), now);
+    }
+}
+
+fn is_current(processed_at : SystemTime, now : SystemTime, retention : Duration) -> bool {
+    now.duration_since(processed_at).unwrap_or_default() < retention
+}
+
+// ----------------- In Memory -----------------
+
+/// An `EventStore` which keeps event IDs in memory, so they are forgotten when the program exits.
+#[derive(Debug)]
+pub struct InMemoryEventStore {
+    entries : Mutex<Entries>,
+}
+
+impl InMemoryEventStore {
+    /// Creates an empty store which remembers each event ID for the given retention window.
+    pub fn new(retention : Duration) -> Self {
+        InMemoryEventStore {
+            entries : Mutex::new(Entries::new(retention)),
+        }
+    }
+}
+
+#[async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn contains(&self, event_id : &str) -> Result<bool, error::Error> {
+        Ok(self.entries.lock().unwrap().contains(event_id, SystemTime::now()))
+    }
+
+    async fn insert(&self, event_id : &str) -> Result<(), error::Error> {
+        self.entries.lock().unwrap().insert(event_id, SystemTime::now());
+        Ok(())
+    }
+}
+
+// ----------------- File -----------------
+
+/// An `EventStore` which keeps event IDs in a JSON file, so they are remembered across restarts. The file maps each ID to the Unix timestamp at which it was processed, and is rewritten on each insert.
+#[derive(Debug)]
+pub struct FileEventStore {
+    path : PathBuf,
+    entries : Mutex<Entries>,
+    /// Held while the file is rewritten, so that concurrent inserts are saved one after another.
+    writing : futures::lock::Mutex<()>,
+}
+
+impl FileEventStore {
+    /// Opens the store at the given path, which is created on the first insert if it does not exist. Each event ID is remembered for the given retention window.
+    pub fn open(path : impl AsRef<Path>, retention : Duration) -> Result<Self, error::Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = Entries::new(retention);
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let processed : BTreeMap<String, u64> =
+                    serde_json::from_str(&contents)
+                    .map_err(|err| error::Error::Storage(format!("Failed to parse {}: {}", path.display(), err)))?;
+
+                entries.processed =
+                    processed
+                    .into_iter()
+                    .map(|(id, seconds)| (id, UNIX_EPOCH + Duration::from_secs(seconds)))
+                    .collect();
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {},
+            Err(err) => return Err(error::Error::Storage(format!("Failed to read {}: {}", path.display(), err))),
+        }
+
+        Ok(FileEventStore {
+            path,
+            entries : Mutex::new(entries),
+            writing : futures::lock::Mutex::new(()),
+        })
+    }
+
+    /// Writes the entries to a temporary file (named by appending `.tmp` to the store's file name) which then replaces the store, so that the store is not left partially written. The files are written on a blocking thread.
+    async fn save(&self, entries : &Entries) -> Result<(), error::Error> {
+        let processed : BTreeMap<&str, u64> =
+            entries.processed
+            .iter()
+            .map(|(id, processed_at)| (id.as_str(), processed_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()))
+            .collect();
+
+        let contents = serde_json::to_string_pretty(&processed).map_err(error::Error::Serialize)?;
+        let write_error = |err : &dyn std::fmt::Display| error::Error::Storage(format!("Failed to write {}: {}", self.path.display(), err));
+
+        let path = self.path.clone();
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(".tmp");
+
+        tokio::task::spawn_blocking(move || std::fs::write(&temporary, contents).and_then(|()| std::fs::rename(&temporary, &path)))
+            .await
+            .map_err(|err| write_error(&err))?
+            .map_err(|err| write_error(&err))
+    }
+}
+
+#[async_trait]
+impl EventStore for FileEventStore {
+    async fn contains(&self, event_id : &str) -> Result<bool, error::Error> {
+        Ok(self.entries.lock().unwrap().contains(event_id, SystemTime::now()))
+    }
+
+    async fn insert(&self, event_id : &str) -> Result<(), error::Error> {
+        let _writing = self.writing.lock().await;
+
+        // The entries are only updated once saved, so that the store never remembers an event its file does not.
+        let mut entries = self.entries.lock().unwrap().clone();
+        entries.insert(event_id, SystemTime::now());
+
+        self.save(&entries).await?;
+        *self.entries.lock().unwrap() = entries;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire_after_the_retention_window() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_704_031_200);
+        let mut entries = Entries::new(Duration::from_secs(60));
+
+        entries.insert("event-1", start);
+        assert!(entries.contains("event-1", start + Duration::from_secs(59)));
+        assert!(!entries.contains("event-1", start + Duration::from_secs(60)));
+        assert!(!entries.contains("event-2", start));
+
+        entries.insert("event-2", start + Duration::from_secs(90));
+        assert_eq!(entries.processed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn files_are_remembered_across_restarts() {
+        let path = std::env::temp_dir().join(format!("up-api-events-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let store = FileEventStore::open(&path, Duration::from_secs(3600)).unwrap();
+        assert!(!store.contains("event-1").await.unwrap());
+        store.insert("event-1").await.unwrap();
+
+        let reopened = FileEventStore::open(&path, Duration::from_secs(3600)).unwrap();
+        assert!(reopened.contains("event-1").await.unwrap());
+
+        let expired = FileEventStore::open(&path, Duration::ZERO).unwrap();
+        assert!(!expired.contains("event-1").await.unwrap());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(FileEventStore::open(&path, Duration::ZERO), Err(error::Error::Storage(_))));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn files_are_replaced_through_a_sibling_temporary_file() {
+        let directory = std::env::temp_dir().join(format!("up-api-event-store-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("events.json");
+        std::fs::write(directory.join("events.tmp"), "unrelated").unwrap();
+
+        let store = FileEventStore::open(&path, Duration::from_secs(3600)).unwrap();
+        let inserts : Vec<_> = (0..8).map(|i| format!("event-{}", i)).collect();
+        futures::future::try_join_all(inserts.iter().map(|id| store.insert(id))).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(directory.join("events.tmp")).unwrap(), "unrelated");
+        assert!(!directory.join("events.json.tmp").exists());
+
+        let reopened = FileEventStore::open(&path, Duration::from_secs(3600)).unwrap();
+        for id in &inserts {
+            assert!(reopened.contains(id).await.unwrap(), "{}", id);
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_saves_are_not_remembered() {
+        let path = std::env::temp_dir().join(format!("up-api-missing-{}", std::process::id())).join("events.json");
+        let store = FileEventStore::open(&path, Duration::from_secs(3600)).unwrap();
+
+        assert!(matches!(store.insert("event-1").await, Err(error::Error::Storage(_))));
+        assert!(!store.contains("event-1").await.unwrap());
+    }
+}
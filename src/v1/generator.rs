@@ -0,0 +1,619 @@
+use crate::v1::{accounts, categories, standard, transactions};
+use crate::v1::memory::money;
+
+use std::time::{Duration, SystemTime};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// The offset from UTC of all generated date-times (Australian Eastern Standard Time).
+const OFFSET : i32 = 10 * 3600;
+
+/// The number of seconds in a day.
+const DAY : i64 = 86400;
+
+/// The time at which generated histories end by default, such that the output does not depend on when it is generated (2024-01-01T00:00:00+10:00).
+const DEFAULT_END : i64 = 1_704_031_200;
+
+/// The ID and name of a category.
+pub type CategoryName = (&'static str, &'static str);
+
+/// The categories of the Up application, as parent categories (with their IDs and names) and their children.
+pub const CATEGORIES : &[(&str, &str, &[CategoryName])] = &[
+    ("good-life", "Good Life", &[
+        ("games-and-software", "Apps, Games & Software"),
+        ("booze", "Booze"),
+        ("events-and-gigs", "Events & Gigs"),
+        ("hobbies", "Hobbies"),
+        ("holidays-and-travel", "Holidays & Travel"),
+        ("lottery-and-gambling", "Lottery & Gambling"),
+        ("pubs-and-bars", "Pubs & Bars"),
+        ("restaurants-and-cafes", "Restaurants & Cafes"),
+        ("takeaway", "Takeaway"),
+        ("tobacco-and-vaping", "Tobacco & Vaping"),
+        ("tv-and-music", "TV, Music & Streaming"),
+        ("adult", "Adult"),
+    ]),
+    ("personal", "Personal", &[
+        ("children-and-family", "Children & Family"),
+        ("clothing-and-accessories", "Clothing & Accessories"),
+        ("education-and-student-loans", "Education & Student Loans"),
+        ("fitness-and-wellbeing", "Fitness & Wellbeing"),
+        ("gifts-and-charity", "Gifts & Charity"),
+        ("hair-and-beauty", "Hair & Beauty"),
+        ("health-and-medical", "Health & Medical"),
+        ("investments", "Investments"),
+        ("life-admin", "Life Admin"),
+        ("mobile-phone", "Mobile Phone"),
+        ("news-magazines-and-books", "News, Magazines & Books"),
+        ("technology", "Technology"),
+    ]),
+    ("home", "Home", &[
+        ("groceries", "Groceries"),
+        ("homeware-and-appliances", "Homeware & Appliances"),
+        ("internet", "Internet"),
+        ("home-maintenance-and-improvements", "Maintenance & Improvements"),
+        ("pets", "Pets"),
+        ("home-insurance-and-rates", "Rates & Insurance"),
+        ("rent-and-mortgage", "Rent & Mortgage"),
+        ("utilities", "Utilities"),
+    ]),
+    ("transport", "Transport", &[
+        ("car-insurance-and-maintenance", "Car Insurance, Rego & Maintenance"),
+        ("cycling", "Cycling"),
+        ("fuel", "Fuel"),
+        ("parking", "Parking"),
+        ("public-transport", "Public Transport"),
+        ("car-repayments", "Repayments"),
+        ("taxis-and-share-cars", "Taxis & Share Cars"),
+        ("toll-roads", "Tolls"),
+    ]),
+];
+
+/// The tags which are randomly attached to purchases.
+const TAGS : &[&str] = &["Holiday", "Work", "Reimbursable", "Date Night", "Split"];
+
+struct Merchant {
+    description : &'static str,
+    raw_text : &'static str,
+    category : &'static str,
+    /// The range of amounts spent, in cents.
+    amount : (i64, i64),
+    /// The currency in which the merchant charges, if not Australian dollars.
+    currency : Option<&'static str>,
+    method : &'static str,
+}
+
+const MERCHANTS : &[Merchant] = &[
+    Merchant { description : "Woolworths", raw_text : "WOOLWORTHS 1234 SYDNEY", category : "groceries", amount : (800, 12000), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Coles", raw_text : "COLES 0456 MELBOURNE", category : "groceries", amount : (500, 15000), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Aldi", raw_text : "ALDI STORES - BRISBANE", category : "groceries", amount : (1000, 9000), currency : None, method : "CARD_PIN" },
+    Merchant { description : "Bills", raw_text : "BILLS SURRY HILLS", category : "restaurants-and-cafes", amount : (450, 4500), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Guzman y Gomez", raw_text : "GYG NEWTOWN", category : "takeaway", amount : (1200, 3000), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Uber Eats", raw_text : "UBER *EATS HELP.UBER.COM", category : "takeaway", amount : (2000, 6000), currency : None, method : "CARD_ON_FILE" },
+    Merchant { description : "Opal", raw_text : "TRANSPORTFORNSW OPAL", category : "public-transport", amount : (300, 900), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Caltex", raw_text : "CALTEX ST PETERS", category : "fuel", amount : (4000, 11000), currency : None, method : "CARD_PIN" },
+    Merchant { description : "Uber", raw_text : "UBER *TRIP HELP.UBER.COM", category : "taxis-and-share-cars", amount : (1000, 4500), currency : None, method : "CARD_ON_FILE" },
+    Merchant { description : "Netflix", raw_text : "NETFLIX.COM", category : "tv-and-music", amount : (1699, 1699), currency : None, method : "CARD_ON_FILE" },
+    Merchant { description : "Spotify", raw_text : "Spotify P1A2B3C4D5", category : "tv-and-music", amount : (1299, 1299), currency : None, method : "CARD_ON_FILE" },
+    Merchant { description : "Steam", raw_text : "STEAMGAMES.COM 4259522985", category : "games-and-software", amount : (500, 8000), currency : Some("USD"), method : "ECOMMERCE" },
+    Merchant { description : "Amazon", raw_text : "AMAZON MKTPLC AU", category : "technology", amount : (1500, 25000), currency : None, method : "ECOMMERCE" },
+    Merchant { description : "Dan Murphy's", raw_text : "DAN MURPHYS 5123", category : "booze", amount : (2000, 9000), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "The Courthouse Hotel", raw_text : "COURTHOUSE HOTEL NEWTOWN", category : "pubs-and-bars", amount : (900, 6000), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Chemist Warehouse", raw_text : "CHEMIST WAREHOUSE 0632", category : "health-and-medical", amount : (500, 6000), currency : None, method : "CONTACTLESS" },
+    Merchant { description : "Kmart", raw_text : "KMART 1045", category : "homeware-and-appliances", amount : (500, 10000), currency : None, method : "CARD_PIN" },
+    Merchant { description : "Anytime Fitness", raw_text : "ANYTIME FITNESS", category : "fitness-and-wellbeing", amount : (1500, 1500), currency : None, method : "CARD_DETAILS" },
+    Merchant { description : "Airbnb", raw_text : "AIRBNB * HMABCDEFGH", category : "holidays-and-travel", amount : (15000, 60000), currency : Some("USD"), method : "ECOMMERCE" },
+    Merchant { description : "Apple", raw_text : "APPLE.COM/BILL", category : "games-and-software", amount : (149, 1499), currency : None, method : "CARD_ON_FILE" },
+];
+
+/// A small, seeded pseudorandom number generator (SplitMix64), so that the same seed always produces the same data on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number between `low` and `high`, inclusive.
+    fn range(&mut self, low : i64, high : i64) -> i64 {
+        low + (self.next() % ((high - low) as u64 + 1)) as i64
+    }
+
+    /// Whether an event with the given percentage chance happens.
+    fn chance(&mut self, percent : u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn pick<'a, T>(&mut self, values : &'a [T]) -> &'a T {
+        &values[(self.next() % values.len() as u64) as usize]
+    }
+
+    /// An identifier in the format of those generated by the API.
+    fn id(&mut self) -> String {
+        let (a, b) = (self.next(), self.next());
+        format!("{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}", a >> 32, (a >> 16) & 0xFFFF, a & 0xFFF, 0x8000 | ((b >> 48) & 0x3FFF), b & 0xFFFF_FFFF_FFFF)
+    }
+}
+
+/// A generator of realistic (but entirely fictional) accounts and transaction histories, for demos and tests. The same seed and settings always produce the same data.
+///
+/// ```
+/// use up_api::v1::generator::Generator;
+///
+/// let dataset = Generator::new(42).days(30).generate();
+///
+/// let transactions = dataset.transactions();
+/// assert!(transactions.iter().any(|t| t.attributes.round_up.is_some()));
+/// assert_eq!(dataset.transactions_json().len(), transactions.len());
+/// ```
+pub struct Generator {
+    seed : u64,
+    days : u32,
+    purchases_per_day : u32,
+    end : i64,
+    joint_account : bool,
+}
+
+impl Generator {
+    /// Creates a generator from a seed, producing 90 days of history ending on the 1st of January 2024, with a 2Up joint account.
+    pub fn new(seed : u64) -> Self {
+        Generator {
+            seed,
+            days : 90,
+            purchases_per_day : 3,
+            end : DEFAULT_END,
+            joint_account : true,
+        }
+    }
+
+    /// Sets the number of days of history to generate.
+    pub fn days(mut self, value : u32) -> Self {
+        self.days = value;
+        self
+    }
+
+    /// Sets the average number of purchases made each day.
+    pub fn purchases_per_day(mut self, value : u32) -> Self {
+        self.purchases_per_day = value;
+        self
+    }
+
+    /// Sets the time at which the history ends. Transactions in the last two days before this are still `HELD`.
+    pub fn end(mut self, value : SystemTime) -> Self {
+        self.end = value.duration_since(SystemTime::UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs() as i64;
+        self
+    }
+
+    /// Sets whether a 2Up account, jointly owned with another person, is generated.
+    pub fn joint_account(mut self, value : bool) -> Self {
+        self.joint_account = value;
+        self
+    }
+
+    /// Generates the accounts, categories and transactions.
+    pub fn generate(&self) -> Dataset {
+        let mut rng = Rng(self.seed);
+        let start = self.end - self.days as i64 * DAY;
+        let card_number_suffix = format!("{:04}", rng.range(0, 9999));
+
+        let mut accounts = vec![
+            GeneratedAccount::new(&mut rng, "Spending", "TRANSACTIONAL", "INDIVIDUAL", 50000),
+            GeneratedAccount::new(&mut rng, "Savings", "SAVER", "INDIVIDUAL", 500000),
+        ];
+
+        if self.joint_account {
+            accounts.push(GeneratedAccount::new(&mut rng, "2Up Spending", "TRANSACTIONAL", "JOINT", 20000));
+        }
+
+        let mut history = History {
+            rng,
+            end : self.end,
+            card_number_suffix,
+            transactions : Vec::new(),
+        };
+
+        for day in 0..self.days as i64 {
+            let midnight = start + day * DAY - (start + OFFSET as i64).rem_euclid(DAY);
+
+            if day % 14 == 0 {
+                history.income(&accounts[0].id, midnight + 9 * 3600, "Salary", "ACME PTY LTD SALARY", 320000);
+                history.transfer(&accounts[0], &accounts[1], midnight + 9 * 3600 + 300, 50000);
+            }
+
+            if self.joint_account && day % 7 == 3 {
+                history.transfer(&accounts[0], &accounts[2], midnight + 18 * 3600, 10000);
+            }
+
+            let purchases = history.rng.range(0, 2 * self.purchases_per_day as i64);
+
+            for _ in 0..purchases {
+                let time = midnight + history.rng.range(7 * 3600, 22 * 3600);
+
+                if time >= self.end {
+                    continue;
+                }
+
+                let joint = self.joint_account && history.rng.chance(20);
+                let account = if joint { &accounts[2] } else { &accounts[0] };
+                history.purchase(&account.id, time, !joint);
+            }
+        }
+
+        let mut transactions = history.transactions;
+        transactions.sort_by_key(|(time, _)| -time);
+
+        for account in &mut accounts {
+            account.balance +=
+                transactions
+                .iter()
+                .filter(|(_, t)| t["relationships"]["account"]["data"]["id"] == account.id.as_str())
+                .map(|(_, t)| t["attributes"]["amount"]["valueInBaseUnits"].as_i64().unwrap_or(0))
+                .sum::<i64>();
+        }
+
+        Dataset {
+            accounts : accounts.iter().map(|a| a.to_json(start - 365 * DAY)).collect(),
+            categories : category_resources(),
+            transactions : transactions.into_iter().map(|(_, t)| t).collect(),
+        }
+    }
+}
+
+struct GeneratedAccount {
+    id : String,
+    display_name : &'static str,
+    account_type : &'static str,
+    ownership_type : &'static str,
+    balance : i64,
+}
+
+impl GeneratedAccount {
+    fn new(rng : &mut Rng, display_name : &'static str, account_type : &'static str, ownership_type : &'static str, opening_balance : i64) -> Self {
+        GeneratedAccount {
+            id : rng.id(),
+            display_name,
+            account_type,
+            ownership_type,
+            balance : opening_balance,
+        }
+    }
+
+    fn to_json(&self, created_at : i64) -> Value {
+        json!({
+            "type" : "accounts",
+            "id" : self.id,
+            "attributes" : {
+                "displayName" : self.display_name,
+                "accountType" : self.account_type,
+                "ownershipType" : self.ownership_type,
+                "balance" : money(self.balance, "AUD"),
+                "createdAt" : standard::format_timestamp(created_at, OFFSET),
+            },
+            "relationships" : {
+                "transactions" : {},
+            },
+        })
+    }
+}
+
+/// The transactions generated so far, each with the time it was created.
+struct History {
+    rng : Rng,
+    end : i64,
+    card_number_suffix : String,
+    transactions : Vec<(i64, Value)>,
+}
+
+impl History {
+    /// Creates a transaction which is `HELD` if it is recent enough, and otherwise settled up to two days after it was created.
+    fn push(&mut self, account_id : &str, time : i64, attributes : Value, relationships : Value) {
+        let held = time > self.end - 2 * DAY;
+        let settled_at = (time + self.rng.range(0, 2 * DAY)).min(self.end);
+
+        let mut attributes = attributes;
+        attributes["status"] = json!(if held { "HELD" } else { "SETTLED" });
+        attributes["settledAt"] = if held { Value::Null } else { json!(standard::format_timestamp(settled_at, OFFSET)) };
+        attributes["createdAt"] = json!(standard::format_timestamp(time, OFFSET));
+
+        let mut relationships = relationships;
+        relationships["account"] = json!({ "data" : { "type" : "accounts", "id" : account_id } });
+
+        for name in ["transferAccount", "category", "parentCategory"] {
+            if relationships.get(name).is_none() {
+                relationships[name] = json!({ "data" : null });
+            }
+        }
+
+        if relationships.get("tags").is_none() {
+            relationships["tags"] = json!({ "data" : [] });
+        }
+
+        let transaction = json!({
+            "type" : "transactions",
+            "id" : self.rng.id(),
+            "attributes" : attributes,
+            "relationships" : relationships,
+        });
+
+        self.transactions.push((time, transaction));
+    }
+
+    fn income(&mut self, account_id : &str, time : i64, description : &str, raw_text : &str, cents : i64) {
+        self.push(account_id, time, attributes(description, Some(raw_text), cents, false), json!({}));
+    }
+
+    /// Creates the pair of transactions for a transfer between two of the customer's accounts.
+    fn transfer(&mut self, from : &GeneratedAccount, to : &GeneratedAccount, time : i64, cents : i64) {
+        let mut outgoing = attributes(&format!("Transfer to {}", to.display_name), None, -cents, false);
+        outgoing["message"] = json!(format!("Transfer to {}", to.display_name));
+        self.push(&from.id, time, outgoing, json!({ "transferAccount" : { "data" : { "type" : "accounts", "id" : to.id } } }));
+
+        let incoming = attributes(&format!("Transfer from {}", from.display_name), None, cents, false);
+        self.push(&to.id, time, incoming, json!({ "transferAccount" : { "data" : { "type" : "accounts", "id" : from.id } } }));
+    }
+
+    /// Creates a card purchase, which may have a round up (for purchases from the customer's own spending account), cashback, a foreign amount and tags.
+    fn purchase(&mut self, account_id : &str, time : i64, round_up : bool) {
+        let merchant = self.rng.pick(MERCHANTS);
+        let cents = self.rng.range(merchant.amount.0, merchant.amount.1);
+
+        let mut attributes = attributes(merchant.description, Some(merchant.raw_text), -cents, true);
+
+        attributes["cardPurchaseMethod"] = json!({
+            "method" : merchant.method,
+            "cardNumberSuffix" : self.card_number_suffix,
+        });
+
+        if let Some(currency) = merchant.currency {
+            // Roughly 0.66 units of foreign currency to the dollar.
+            attributes["foreignAmount"] = money(-cents * 66 / 100, currency);
+        }
+
+        attributes["holdInfo"] = json!({
+            "amount" : attributes["amount"],
+            "foreignAmount" : attributes["foreignAmount"],
+        });
+
+        if round_up && cents % 100 != 0 && self.rng.chance(40) {
+            let boost = if self.rng.chance(10) { 100 } else { 0 };
+            let amount = 100 - cents % 100 + boost;
+
+            attributes["roundUp"] = json!({
+                "amount" : money(-amount, "AUD"),
+                "boostPortion" : if boost > 0 { money(-boost, "AUD") } else { Value::Null },
+            });
+        }
+
+        if self.rng.chance(3) {
+            attributes["cashback"] = json!({
+                "description" : format!("Cashback on your {} purchase", merchant.description),
+                "amount" : money((cents / 20).max(1), "AUD"),
+            });
+        }
+
+        let parent = CATEGORIES
+            .iter()
+            .find(|(_, _, children)| children.iter().any(|(id, _)| *id == merchant.category))
+            .map(|(id, _, _)| *id);
+
+        let mut relationships = json!({
+            "category" : { "data" : { "type" : "categories", "id" : merchant.category } },
+            "parentCategory" : { "data" : parent.map(|p| json!({ "type" : "categories", "id" : p })) },
+        });
+
+        if self.rng.chance(10) {
+            relationships["tags"] = json!({ "data" : [{ "type" : "tags", "id" : self.rng.pick(TAGS) }] });
+        }
+
+        self.push(account_id, time, attributes, relationships);
+    }
+}
+
+/// The attributes of a transaction in Australian dollars, without any of the optional details.
+fn attributes(description : &str, raw_text : Option<&str>, cents : i64, categorizable : bool) -> Value {
+    json!({
+        "rawText" : raw_text,
+        "description" : description,
+        "message" : null,
+        "isCategorizable" : categorizable,
+        "holdInfo" : null,
+        "roundUp" : null,
+        "cashback" : null,
+        "amount" : money(cents, "AUD"),
+        "foreignAmount" : null,
+        "cardPurchaseMethod" : null,
+    })
+}
+
+fn category_resources() -> Vec<Value> {
+    let resource = |id : &str, name : &str, parent : Option<&str>, children : &[(&str, &str)]| json!({
+        "type" : "categories",
+        "id" : id,
+        "attributes" : {
+            "name" : name,
+        },
+        "relationships" : {
+            "parent" : { "data" : parent.map(|p| json!({ "type" : "categories", "id" : p })) },
+            "children" : { "data" : children.iter().map(|(c, _)| json!({ "type" : "categories", "id" : c })).collect::<Vec<_>>() },
+        },
+    });
+
+    CATEGORIES
+        .iter()
+        .flat_map(|(id, name, children)| {
+            std::iter::once(resource(id, name, None, children))
+                .chain(children.iter().map(move |(child, child_name)| resource(child, child_name, Some(id), &[])))
+        })
+        .collect()
+}
+
+/// The output of a `Generator`, available both as the typed resources of this crate and as the JSON returned by the API.
+#[derive(Clone, Debug)]
+pub struct Dataset {
+    accounts : Vec<Value>,
+    categories : Vec<Value>,
+    transactions : Vec<Value>,
+}
+
+impl Dataset {
+    /// The accounts, as returned by `list_accounts`.
+    pub fn accounts(&self) -> Vec<accounts::AccountResource> {
+        typed(&self.accounts)
+    }
+
+    /// The categories, as returned by `list_categories`.
+    pub fn categories(&self) -> Vec<categories::CategoryResource> {
+        typed(&self.categories)
+    }
+
+    /// The transactions of all accounts, newest first, as returned by `list_transactions`.
+    pub fn transactions(&self) -> Vec<transactions::TransactionResource> {
+        typed(&self.transactions)
+    }
+
+    /// The accounts as JSON resources.
+    pub fn accounts_json(&self) -> &[Value] {
+        &self.accounts
+    }
+
+    /// The categories as JSON resources.
+    pub fn categories_json(&self) -> &[Value] {
+        &self.categories
+    }
+
+    /// The transactions as JSON resources, newest first.
+    pub fn transactions_json(&self) -> &[Value] {
+        &self.transactions
+    }
+
+    /// Creates a `Store` containing this data, to be served by an `InMemoryApi` or a `TestServer`.
+    pub fn into_store(self) -> crate::v1::memory::Store {
+        let store = crate::v1::memory::Store::new();
+
+        for resource in self.accounts {
+            store.insert_account(resource);
+        }
+
+        for resource in self.categories {
+            store.insert_category(resource);
+        }
+
+        for resource in self.transactions {
+            store.insert_transaction(resource);
+        }
+
+        store
+    }
+}
+
+fn typed<T : DeserializeOwned>(values : &[Value]) -> Vec<T> {
+    values
+        .iter()
+        .map(|value| serde_json::from_value(value.clone()).expect("Generated resources always match the types of the API."))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_seed_produces_the_same_data() {
+        let first = Generator::new(7).days(20).generate();
+        let second = Generator::new(7).days(20).generate();
+        let other = Generator::new(8).days(20).generate();
+
+        assert_eq!(first.transactions_json(), second.transactions_json());
+        assert_ne!(first.transactions_json(), other.transactions_json());
+    }
+
+    #[test]
+    fn histories_are_realistic() {
+        let dataset = Generator::new(1).generate();
+        let accounts = dataset.accounts();
+        let transactions = dataset.transactions();
+
+        assert_eq!(accounts.len(), 3);
+        assert!(accounts.iter().any(|a| a.attributes.ownership_type == "JOINT"));
+        assert_eq!(dataset.categories().len(), CATEGORIES.iter().map(|(_, _, c)| c.len() + 1).sum::<usize>());
+
+        let statuses : Vec<standard::TransactionStatusEnum> = transactions.iter().map(|t| t.attributes.status).collect();
+        assert!(statuses.contains(&standard::TransactionStatusEnum::Held));
+        assert!(statuses.contains(&standard::TransactionStatusEnum::Settled));
+
+        assert!(transactions.iter().any(|t| t.attributes.round_up.is_some()));
+        assert!(transactions.iter().any(|t| t.attributes.cashback.is_some()));
+        assert!(transactions.iter().any(|t| t.attributes.foreign_amount.is_some()));
+        assert!(transactions.iter().any(|t| t.attributes.card_purchase_method.is_some()));
+        assert!(transactions.iter().any(|t| t.relationships.transfer_account.data.is_some()));
+        assert!(transactions.iter().any(|t| !t.relationships.tags.data.is_empty()));
+
+        // Every transfer has a matching transfer of the opposite amount, at the same time, on the other account.
+        let transfers : Vec<_> = transactions.iter().filter(|t| t.relationships.transfer_account.data.is_some()).collect();
+        let mut matched = vec![false; transfers.len()];
+
+        for (i, transfer) in transfers.iter().enumerate().filter(|(_, t)| t.attributes.amount.value_in_base_units < 0) {
+            let other = transfer.relationships.transfer_account.data.as_ref().unwrap();
+
+            let j = transfers.iter().enumerate().position(|(j, t)| {
+                !matched[j]
+                    && t.relationships.account.data.id == other.id
+                    && t.relationships.transfer_account.data.as_ref().is_some_and(|a| a.id == transfer.relationships.account.data.id)
+                    && t.attributes.amount.value_in_base_units == -transfer.attributes.amount.value_in_base_units
+                    && t.attributes.created_at == transfer.attributes.created_at
+            });
+
+            let j = j.unwrap_or_else(|| panic!("{} has no matching transfer", transfer.id));
+            matched[i] = true;
+            matched[j] = true;
+        }
+
+        assert!(matched.iter().all(|m| *m));
+
+        // The balance of each account is consistent with its transactions.
+        let spending = &accounts[0];
+        let total : i64 =
+            transactions
+            .iter()
+            .filter(|t| t.relationships.account.data.id == spending.id)
+            .map(|t| t.attributes.amount.value_in_base_units)
+            .sum();
+        assert_eq!(spending.attributes.balance.value_in_base_units, 50000 + total);
+    }
+
+    #[test]
+    fn histories_do_not_extend_past_the_end() {
+        let end = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let dataset = Generator::new(3).days(10).end(end).joint_account(false).generate();
+
+        let latest = dataset.transactions_json()[0]["attributes"]["createdAt"].as_str().unwrap().to_string();
+
+        assert!(standard::parse_date_time("createdAt", &latest).unwrap().0 < 1_700_000_000);
+        assert_eq!(dataset.accounts().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn datasets_can_be_served() {
+        use futures::TryStreamExt;
+
+        let dataset = Generator::new(5).days(14).generate();
+        let expected = dataset.transactions_json().len();
+
+        let api = crate::v1::memory::InMemoryApi::new(dataset.into_store());
+
+        let transactions : Vec<_> =
+            api
+            .client()
+            .list_transactions_stream(&Default::default(), crate::v1::pagination::StreamLimits::default())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(transactions.len(), expected);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::v1::{error, scrub, transport};
+
+use std::time::Duration;
+
+/// Creates the span covering a request (including any retries) to the API. The `status`, `latency_ms` and `retries` fields are recorded once responses arrive.
+///
+/// Only the path of the URL is recorded as the endpoint, so that filter values in the query are left out.
+pub(crate) fn request_span(method : &reqwest::Method, url : &reqwest::Url, page : Option<usize>) -> tracing::Span {
+    tracing::debug_span!(
+        "up_api.request",
+        method = %method,
+        endpoint = %endpoint(url),
+        page = page,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        retries = tracing::field::Empty,
+    )
+}
+
+/// Records the result of an attempt at a request against the current span, along with the request and response bodies (at the `TRACE` level) with the access token and the fields in `scrub::DEFAULT_SCRUBBED_FIELDS` redacted.
+pub(crate) fn record_attempt(access_token : &str, body : Option<&str>, result : &Result<transport::Response, error::Error>, attempt : u32, elapsed : Duration) {
+    let span = tracing::Span::current();
+
+    span.record("latency_ms", elapsed.as_millis() as u64);
+    span.record("retries", attempt - 1);
+
+    if let Some(body) = body {
+        tracing::trace!(body = %scrub(body, access_token), "request body");
+    }
+
+    match result {
+        Ok(res) => {
+            span.record("status", res.status.as_u16());
+            tracing::debug!(status = res.status.as_u16(), attempt, "response received");
+            tracing::trace!(body = %scrub(&res.body, access_token), "response body");
+        },
+        Err(err) => {
+            tracing::debug!(error = %scrub(&err.to_string(), access_token), attempt, "request failed");
+        },
+    }
+}
+
+/// Records that an attempt is about to be retried after the given delay.
+pub(crate) fn record_retry(attempt : u32, delay : Duration) {
+    tracing::debug!(attempt, delay_ms = delay.as_millis() as u64, "retrying request");
+}
+
+fn endpoint(url : &reqwest::Url) -> &str {
+    url.path()
+}
+
+fn scrub(text : &str, access_token : &str) -> String {
+    scrub::scrub_text(text, Some(access_token), scrub::DEFAULT_SCRUBBED_FIELDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitive_values_are_not_recorded() {
+        let url = reqwest::Url::parse("https://api.up.com.au/api/v1/transactions?filter%5Bsearch%5D=secret").unwrap();
+        assert_eq!(endpoint(&url), "/api/v1/transactions");
+
+        let body = r#"{"data":{"attributes":{"description":"Rent","url":"https://example.com/up:yeah:token","status":"SETTLED"}}}"#;
+        let scrubbed = scrub(body, "up:yeah:token");
+
+        assert!(!scrubbed.contains("Rent"));
+        assert!(!scrubbed.contains("up:yeah:token"));
+        assert!(scrubbed.contains("SETTLED"));
+        assert_eq!(scrub("Bearer up:yeah:token", "up:yeah:token"), "Bearer [REDACTED]");
+    }
+}
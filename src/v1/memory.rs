@@ -0,0 +1,922 @@
+use crate::v1::{Client, error, standard, transport};
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// The path prefix of the default base URL, which all endpoints are relative to.
+const API_PREFIX : &str = "/api/v1";
+
+/// The number of resources in each page when no `page[size]` is given.
+const DEFAULT_PAGE_SIZE : usize = 10;
+
+/// The maximum number of tags which can be added to a transaction.
+const MAX_TAGS : usize = 6;
+
+// ----------------- Store -----------------
+
+/// The in-memory data behind an `InMemoryApi` (or a `TestServer`). The store is cheaply cloneable, with every clone sharing the same data, so that it can be inspected and modified while it is in use.
+///
+/// Resources are inserted in the shape returned by the API (as `serde_json::Value` objects with a `type`, `id`, `attributes` and `relationships`), without their `links`, which are added for the base URL of each request. Requests which modify data (such as `categorise_transaction` and `create_webhook`) modify the store.
+#[derive(Clone, Debug, Default)]
+pub struct Store {
+    data : Arc<Mutex<Data>>,
+}
+
+#[derive(Debug, Default)]
+struct Data {
+    accounts : Vec<Value>,
+    transactions : Vec<Value>,
+    categories : Vec<Value>,
+    webhooks : Vec<Value>,
+    logs : HashMap<String, Vec<Value>>,
+    counter : u64,
+}
+
+impl Data {
+    /// A unique identifier in the format of those generated by the API. These are deterministic, so that tests see the same identifiers on every run.
+    fn next_id(&mut self) -> String {
+        self.counter += 1;
+        format!("00000000-0000-4000-8000-{:012x}", self.counter)
+    }
+}
+
+impl Store {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a store with a small, fixed set of accounts, categories and transactions.
+    pub fn sample() -> Self {
+        let store = Self::new();
+
+        for (parent, children) in [
+            ("good-life", &["restaurants-and-cafes", "takeaway"][..]),
+            ("home", &["groceries", "rent-and-mortgage"][..]),
+            ("transport", &["fuel", "public-transport"][..]),
+        ] {
+            store.insert_category(category(parent, None, children));
+
+            for child in children {
+                store.insert_category(category(child, Some(parent), &[]));
+            }
+        }
+
+        store.insert_account(account("spending", "Spending", standard::AccountTypeEnum::Transactional, standard::OwnershipTypeEnum::Individual, 15_230, "2021-06-01T09:00:00+10:00"));
+        store.insert_account(account("savings", "Savings", standard::AccountTypeEnum::Saver, standard::OwnershipTypeEnum::Individual, 250_000, "2021-06-01T09:00:00+10:00"));
+
+        let transactions = [
+            ("spending", "Coles", -6_415, "groceries", standard::TransactionStatusEnum::Settled, "2022-03-01T18:30:00+11:00"),
+            ("spending", "Opal", -480, "public-transport", standard::TransactionStatusEnum::Settled, "2022-03-02T08:15:00+11:00"),
+            ("spending", "Bills Cafe", -1_250, "restaurants-and-cafes", standard::TransactionStatusEnum::Settled, "2022-03-02T12:40:00+11:00"),
+            ("savings", "Transfer from Spending", 10_000, "", standard::TransactionStatusEnum::Settled, "2022-03-03T09:00:00+11:00"),
+            ("spending", "Caltex", -5_500, "fuel", standard::TransactionStatusEnum::Held, "2022-03-04T17:05:00+11:00"),
+        ];
+
+        for (i, (account_id, description, cents, category_id, status, created_at)) in transactions.into_iter().enumerate() {
+            let mut resource = transaction(&format!("transaction-{}", i + 1), account_id, description, cents, status, created_at);
+
+            if !category_id.is_empty() {
+                let parent = store.parent_of(category_id);
+                resource["attributes"]["isCategorizable"] = json!(true);
+                resource["relationships"]["category"]["data"] = json!({ "type" : "categories", "id" : category_id });
+                resource["relationships"]["parentCategory"]["data"] = parent.map_or(Value::Null, |p| json!({ "type" : "categories", "id" : p }));
+            }
+
+            store.insert_transaction(resource);
+        }
+
+        store
+    }
+
+    /// Adds an account.
+    pub fn insert_account(&self, resource : Value) {
+        self.data.lock().unwrap().accounts.push(resource);
+    }
+
+    /// Adds a transaction. Transactions are served newest first, according to their `createdAt`.
+    pub fn insert_transaction(&self, resource : Value) {
+        let mut data = self.data.lock().unwrap();
+        let created_at = timestamp(&resource["attributes"]["createdAt"]);
+
+        let index =
+            data.transactions
+            .iter()
+            .position(|t| timestamp(&t["attributes"]["createdAt"]) < created_at)
+            .unwrap_or(data.transactions.len());
+
+        data.transactions.insert(index, resource);
+    }
+
+    /// Adds a category.
+    pub fn insert_category(&self, resource : Value) {
+        self.data.lock().unwrap().categories.push(resource);
+    }
+
+    /// Adds a webhook.
+    pub fn insert_webhook(&self, resource : Value) {
+        self.data.lock().unwrap().webhooks.push(resource);
+    }
+
+    /// All of the transactions in the store, newest first.
+    pub fn transactions(&self) -> Vec<Value> {
+        self.data.lock().unwrap().transactions.clone()
+    }
+
+    /// All of the webhooks in the store.
+    pub fn webhooks(&self) -> Vec<Value> {
+        self.data.lock().unwrap().webhooks.clone()
+    }
+
+    fn parent_of(&self, category_id : &str) -> Option<String> {
+        let data = self.data.lock().unwrap();
+
+        data.categories
+            .iter()
+            .find(|c| c["id"] == category_id)
+            .and_then(|c| c["relationships"]["parent"]["data"]["id"].as_str())
+            .map(String::from)
+    }
+}
+
+/// Creates an account resource.
+pub fn account(id : &str, display_name : &str, account_type : standard::AccountTypeEnum, ownership_type : standard::OwnershipTypeEnum, balance_in_cents : i64, created_at : &str) -> Value {
+    json!({
+        "type" : "accounts",
+        "id" : id,
+        "attributes" : {
+            "displayName" : display_name,
+            "accountType" : account_type.to_string(),
+            "ownershipType" : ownership_type.to_string(),
+            "balance" : money(balance_in_cents, "AUD"),
+            "createdAt" : created_at,
+        },
+        "relationships" : {
+            "transactions" : {},
+        },
+    })
+}
+
+/// Creates a category resource, with the given parent and children.
+pub fn category(id : &str, parent : Option<&str>, children : &[&str]) -> Value {
+    let name = id
+        .split('-')
+        .map(|word| {
+            if word == "and" {
+                return String::from("&");
+            }
+
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    json!({
+        "type" : "categories",
+        "id" : id,
+        "attributes" : {
+            "name" : name,
+        },
+        "relationships" : {
+            "parent" : {
+                "data" : parent.map(|p| json!({ "type" : "categories", "id" : p })),
+            },
+            "children" : {
+                "data" : children.iter().map(|c| json!({ "type" : "categories", "id" : c })).collect::<Vec<_>>(),
+            },
+        },
+    })
+}
+
+/// Creates an uncategorised transaction in Australian dollars, with no tags.
+pub fn transaction(id : &str, account_id : &str, description : &str, amount_in_cents : i64, status : standard::TransactionStatusEnum, created_at : &str) -> Value {
+    let settled_at = match status {
+        standard::TransactionStatusEnum::Settled => json!(created_at),
+        standard::TransactionStatusEnum::Held => Value::Null,
+    };
+
+    json!({
+        "type" : "transactions",
+        "id" : id,
+        "attributes" : {
+            "status" : status.to_string(),
+            "rawText" : Value::Null,
+            "description" : description,
+            "message" : Value::Null,
+            "isCategorizable" : false,
+            "holdInfo" : Value::Null,
+            "roundUp" : Value::Null,
+            "cashback" : Value::Null,
+            "amount" : money(amount_in_cents, "AUD"),
+            "foreignAmount" : Value::Null,
+            "cardPurchaseMethod" : Value::Null,
+            "settledAt" : settled_at,
+            "createdAt" : created_at,
+        },
+        "relationships" : {
+            "account" : { "data" : { "type" : "accounts", "id" : account_id } },
+            "transferAccount" : { "data" : Value::Null },
+            "category" : { "data" : Value::Null },
+            "parentCategory" : { "data" : Value::Null },
+            "tags" : { "data" : [] },
+        },
+    })
+}
+
+/// Creates a `MoneyObject` from an amount in cents (or the base unit of the currency).
+pub(crate) fn money(cents : i64, currency_code : &str) -> Value {
+    let sign = if cents < 0 { "-" } else { "" };
+
+    json!({
+        "currencyCode" : currency_code,
+        "value" : format!("{}{}.{:02}", sign, cents.unsigned_abs() / 100, cents.unsigned_abs() % 100),
+        "valueInBaseUnits" : cents,
+    })
+}
+
+/// The seconds and nanoseconds since the Unix epoch of a date-time, for sorting and filtering.
+fn timestamp(value : &Value) -> Option<(i64, u32)> {
+    standard::parse_date_time("date-time", value.as_str()?).ok()
+}
+
+// ----------------- Requests -----------------
+
+impl Store {
+    /// Responds to a request as the API would, reading and modifying the data in the store. Any bearer token is accepted.
+    pub fn respond(&self, request : &transport::Request) -> transport::Response {
+        let reply = self.route(request);
+
+        match reply.body {
+            Some(body) => transport::Response::json(reply.status, body.to_string()),
+            None => transport::Response::empty(reply.status),
+        }
+    }
+
+    fn route(&self, request : &transport::Request) -> Reply {
+        let authorised =
+            request.headers
+            .get(reqwest::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| !token.trim().is_empty());
+
+        if !authorised {
+            return error_response(reqwest::StatusCode::UNAUTHORIZED, "Not Authorized", "The request was not authenticated because no valid credential was found in the Authorization header, or the Authorization header was not present.", None);
+        }
+
+        let base = format!("{}{}", request.url.origin().ascii_serialization(), API_PREFIX);
+
+        let request = Request {
+            store : self,
+            base : &base,
+            url : &request.url,
+            body : request.body.as_deref().unwrap_or_default(),
+            method : &request.method,
+        };
+
+        let path = request.url.path().strip_prefix(API_PREFIX).unwrap_or("");
+        let segments : Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        use reqwest::Method;
+
+        let result = match (request.method, segments.as_slice()) {
+            (&Method::GET, ["util", "ping"]) => Ok(json_response(reqwest::StatusCode::OK, json!({ "meta" : { "id" : self.data.lock().unwrap().next_id(), "statusEmoji" : "⚡️" } }))),
+            (&Method::GET, ["accounts"]) => request.list_accounts(),
+            (&Method::GET, ["accounts", id]) => request.get("accounts", id),
+            (&Method::GET, ["accounts", id, "transactions"]) => request.list_transactions(Some(id)),
+            (&Method::GET, ["transactions"]) => request.list_transactions(None),
+            (&Method::GET, ["transactions", id]) => request.get("transactions", id),
+            (&Method::PATCH, ["transactions", id, "relationships", "category"]) => request.categorise(id),
+            (&Method::POST, ["transactions", id, "relationships", "tags"]) => request.update_tags(id, true),
+            (&Method::DELETE, ["transactions", id, "relationships", "tags"]) => request.update_tags(id, false),
+            (&Method::GET, ["categories"]) => request.list_categories(),
+            (&Method::GET, ["categories", id]) => request.get("categories", id),
+            (&Method::GET, ["tags"]) => request.list_tags(),
+            (&Method::GET, ["webhooks"]) => request.list_webhooks(),
+            (&Method::POST, ["webhooks"]) => request.create_webhook(),
+            (&Method::GET, ["webhooks", id]) => request.get("webhooks", id),
+            (&Method::DELETE, ["webhooks", id]) => request.delete_webhook(id),
+            (&Method::POST, ["webhooks", id, "ping"]) => request.ping_webhook(id),
+            (&Method::GET, ["webhooks", id, "logs"]) => request.list_webhook_logs(id),
+            (_, [..]) => Err(not_found()),
+        };
+
+        result.unwrap_or_else(|err| err)
+    }
+}
+
+/// The parts of a request needed by each endpoint.
+struct Request<'a> {
+    store : &'a Store,
+    base : &'a str,
+    method : &'a reqwest::Method,
+    url : &'a reqwest::Url,
+    body : &'a str,
+}
+
+impl Request<'_> {
+    fn param(&self, name : &str) -> Option<String> {
+        self.url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned())
+    }
+
+    fn get(&self, collection : &str, id : &str) -> Result<Reply, Reply> {
+        let data = self.store.data.lock().unwrap();
+
+        let resources = match collection {
+            "accounts" => &data.accounts,
+            "transactions" => &data.transactions,
+            "categories" => &data.categories,
+            _ => &data.webhooks,
+        };
+
+        let resource = resources.iter().find(|r| r["id"] == id).ok_or_else(not_found)?;
+
+        Ok(json_response(reqwest::StatusCode::OK, json!({ "data" : with_links(resource, self.base) })))
+    }
+
+    fn list_accounts(&self) -> Result<Reply, Reply> {
+        let account_type = self.param("filter[accountType]");
+        let ownership_type = self.param("filter[ownershipType]");
+
+        for (name, value, allowed) in [
+            ("filter[accountType]", &account_type, &["SAVER", "TRANSACTIONAL"][..]),
+            ("filter[ownershipType]", &ownership_type, &["INDIVIDUAL", "JOINT"][..]),
+        ] {
+            if value.as_ref().is_some_and(|v| !allowed.contains(&v.as_str())) {
+                return Err(invalid_parameter(name));
+            }
+        }
+
+        let data = self.store.data.lock().unwrap();
+
+        let accounts : Vec<&Value> =
+            data.accounts
+            .iter()
+            .filter(|a| account_type.as_ref().is_none_or(|t| a["attributes"]["accountType"] == t.as_str()))
+            .filter(|a| ownership_type.as_ref().is_none_or(|t| a["attributes"]["ownershipType"] == t.as_str()))
+            .collect();
+
+        self.page(&accounts)
+    }
+
+    fn list_transactions(&self, account_id : Option<&str>) -> Result<Reply, Reply> {
+        let status = self.param("filter[status]");
+        let category = self.param("filter[category]");
+        let tag = self.param("filter[tag]");
+
+        if status.as_ref().is_some_and(|s| s != "HELD" && s != "SETTLED") {
+            return Err(invalid_parameter("filter[status]"));
+        }
+
+        let since = self.date_time_param("filter[since]")?;
+        let until = self.date_time_param("filter[until]")?;
+
+        let data = self.store.data.lock().unwrap();
+
+        if let Some(id) = account_id {
+            if !data.accounts.iter().any(|a| a["id"] == id) {
+                return Err(not_found());
+            }
+        }
+
+        if let Some(id) = &category {
+            if !data.categories.iter().any(|c| c["id"] == id.as_str()) {
+                return Err(error_response(reqwest::StatusCode::NOT_FOUND, "Not Found", "The category specified in the filter[category] parameter does not exist.", Some(json!({ "parameter" : "filter[category]" }))));
+            }
+        }
+
+        let transactions : Vec<&Value> =
+            data.transactions
+            .iter()
+            .filter(|t| account_id.is_none_or(|id| t["relationships"]["account"]["data"]["id"] == id))
+            .filter(|t| status.as_ref().is_none_or(|s| t["attributes"]["status"] == s.as_str()))
+            .filter(|t| {
+                category.as_ref().is_none_or(|c| {
+                    t["relationships"]["category"]["data"]["id"] == c.as_str() || t["relationships"]["parentCategory"]["data"]["id"] == c.as_str()
+                })
+            })
+            .filter(|t| tag.as_ref().is_none_or(|tag| tags_of(t).contains(tag)))
+            .filter(|t| {
+                let created_at = timestamp(&t["attributes"]["createdAt"]);
+                since.is_none_or(|s| created_at >= Some(s)) && until.is_none_or(|u| created_at < Some(u))
+            })
+            .collect();
+
+        self.page(&transactions)
+    }
+
+    fn list_categories(&self) -> Result<Reply, Reply> {
+        let parent = self.param("filter[parent]");
+        let data = self.store.data.lock().unwrap();
+
+        if let Some(id) = &parent {
+            if !data.categories.iter().any(|c| c["id"] == id.as_str()) {
+                return Err(error_response(reqwest::StatusCode::NOT_FOUND, "Not Found", "The category specified in the filter[parent] parameter does not exist.", Some(json!({ "parameter" : "filter[parent]" }))));
+            }
+        }
+
+        let categories : Vec<Value> =
+            data.categories
+            .iter()
+            .filter(|c| parent.as_ref().is_none_or(|p| c["relationships"]["parent"]["data"]["id"] == p.as_str()))
+            .map(|c| with_links(c, self.base))
+            .collect();
+
+        Ok(json_response(reqwest::StatusCode::OK, json!({ "data" : categories })))
+    }
+
+    fn list_tags(&self) -> Result<Reply, Reply> {
+        let data = self.store.data.lock().unwrap();
+
+        let tags : Vec<Value> =
+            data.transactions
+            .iter()
+            .flat_map(tags_of)
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .map(|tag| json!({ "type" : "tags", "id" : tag, "relationships" : { "transactions" : {} } }))
+            .collect();
+
+        self.page(&tags.iter().collect::<Vec<_>>())
+    }
+
+    fn categorise(&self, id : &str) -> Result<Reply, Reply> {
+        let body : Value = serde_json::from_str(self.body).map_err(|_| invalid_body())?;
+
+        let category = match &body["data"] {
+            Value::Null => None,
+            data => Some(data["id"].as_str().ok_or_else(invalid_body)?),
+        };
+
+        let parent = match category {
+            Some(category) => {
+                let data = self.store.data.lock().unwrap();
+                let resource =
+                    data.categories
+                    .iter()
+                    .find(|c| c["id"] == category)
+                    .ok_or_else(|| error_response(reqwest::StatusCode::UNPROCESSABLE_ENTITY, "Invalid Attribute", "The category does not exist.", Some(json!({ "pointer" : "/data/id" }))))?;
+
+                resource["relationships"]["parent"]["data"]["id"].as_str().map(String::from)
+            },
+            None => None,
+        };
+
+        let mut data = self.store.data.lock().unwrap();
+        let transaction = data.transactions.iter_mut().find(|t| t["id"] == id).ok_or_else(not_found)?;
+
+        if transaction["attributes"]["isCategorizable"] != true {
+            return Err(error_response(reqwest::StatusCode::FORBIDDEN, "Forbidden", "This transaction does not support categorization.", None));
+        }
+
+        transaction["relationships"]["category"]["data"] = category.map_or(Value::Null, |c| json!({ "type" : "categories", "id" : c }));
+        transaction["relationships"]["parentCategory"]["data"] = parent.map_or(Value::Null, |p| json!({ "type" : "categories", "id" : p }));
+
+        Ok(empty_response(reqwest::StatusCode::NO_CONTENT))
+    }
+
+    fn update_tags(&self, id : &str, add : bool) -> Result<Reply, Reply> {
+        let body : Value = serde_json::from_str(self.body).map_err(|_| invalid_body())?;
+
+        let labels : Vec<String> =
+            body["data"]
+            .as_array()
+            .ok_or_else(invalid_body)?
+            .iter()
+            .map(|tag| tag["id"].as_str().map(String::from).ok_or_else(invalid_body))
+            .collect::<Result<_, _>>()?;
+
+        let mut data = self.store.data.lock().unwrap();
+        let transaction = data.transactions.iter_mut().find(|t| t["id"] == id).ok_or_else(not_found)?;
+
+        let mut tags = tags_of(transaction);
+
+        if add {
+            for label in labels {
+                if !tags.contains(&label) {
+                    tags.push(label);
+                }
+            }
+
+            if tags.len() > MAX_TAGS {
+                return Err(error_response(reqwest::StatusCode::UNPROCESSABLE_ENTITY, "Invalid Attribute", &format!("A transaction may have at most {} tags.", MAX_TAGS), Some(json!({ "pointer" : "/data" }))));
+            }
+        }
+        else {
+            tags.retain(|tag| !labels.contains(tag));
+        }
+
+        transaction["relationships"]["tags"]["data"] = tags.iter().map(|t| json!({ "type" : "tags", "id" : t })).collect();
+
+        Ok(empty_response(reqwest::StatusCode::NO_CONTENT))
+    }
+
+    fn list_webhooks(&self) -> Result<Reply, Reply> {
+        let data = self.store.data.lock().unwrap();
+
+        // The secret key is only ever returned when the webhook is created.
+        let webhooks : Vec<Value> =
+            data.webhooks
+            .iter()
+            .map(|w| {
+                let mut w = w.clone();
+                w["attributes"]["secretKey"] = Value::Null;
+                w
+            })
+            .collect();
+
+        self.page(&webhooks.iter().collect::<Vec<_>>())
+    }
+
+    fn create_webhook(&self) -> Result<Reply, Reply> {
+        let body : Value = serde_json::from_str(self.body).map_err(|_| invalid_body())?;
+        let attributes = &body["data"]["attributes"];
+
+        let url = attributes["url"].as_str().ok_or_else(invalid_body)?;
+
+        if reqwest::Url::parse(url).map_or(true, |u| u.scheme() != "http" && u.scheme() != "https") || url.chars().count() > 300 {
+            return Err(error_response(reqwest::StatusCode::UNPROCESSABLE_ENTITY, "Invalid Attribute", "The url must be a valid HTTP or HTTPS URL of at most 300 characters.", Some(json!({ "pointer" : "/data/attributes/url" }))));
+        }
+
+        if attributes["description"].as_str().is_some_and(|d| d.chars().count() > 64) {
+            return Err(error_response(reqwest::StatusCode::UNPROCESSABLE_ENTITY, "Invalid Attribute", "The description must be at most 64 characters.", Some(json!({ "pointer" : "/data/attributes/description" }))));
+        }
+
+        let mut data = self.store.data.lock().unwrap();
+        let id = data.next_id();
+        let secret_key = format!("{}{}", data.next_id().replace('-', ""), data.next_id().replace('-', ""));
+
+        let webhook = json!({
+            "type" : "webhooks",
+            "id" : id,
+            "attributes" : {
+                "url" : url,
+                "description" : attributes["description"],
+                "secretKey" : secret_key,
+                "createdAt" : now(),
+            },
+            "relationships" : {
+                "logs" : {},
+            },
+        });
+
+        data.webhooks.push(webhook.clone());
+
+        Ok(json_response(reqwest::StatusCode::CREATED, json!({ "data" : with_links(&webhook, self.base) })))
+    }
+
+    fn delete_webhook(&self, id : &str) -> Result<Reply, Reply> {
+        let mut data = self.store.data.lock().unwrap();
+        let index = data.webhooks.iter().position(|w| w["id"] == id).ok_or_else(not_found)?;
+
+        data.webhooks.remove(index);
+        data.logs.remove(id);
+
+        Ok(empty_response(reqwest::StatusCode::NO_CONTENT))
+    }
+
+    /// Creates a `PING` event. Events are never delivered to the webhook's URL, but each is logged as though it had been delivered successfully.
+    fn ping_webhook(&self, id : &str) -> Result<Reply, Reply> {
+        let mut data = self.store.data.lock().unwrap();
+
+        if !data.webhooks.iter().any(|w| w["id"] == id) {
+            return Err(not_found());
+        }
+
+        let created_at = now();
+
+        let event = json!({
+            "type" : "webhook-events",
+            "id" : data.next_id(),
+            "attributes" : {
+                "eventType" : "PING",
+                "createdAt" : created_at,
+            },
+            "relationships" : {
+                "webhook" : {
+                    "data" : { "type" : "webhooks", "id" : id },
+                    "links" : { "related" : format!("{}/webhooks/{}", self.base, id) },
+                },
+            },
+        });
+
+        let log = json!({
+            "type" : "webhook-delivery-logs",
+            "id" : data.next_id(),
+            "attributes" : {
+                "request" : { "body" : json!({ "data" : event }).to_string() },
+                "response" : { "statusCode" : 200, "body" : "" },
+                "deliveryStatus" : "DELIVERED",
+                "createdAt" : created_at,
+            },
+            "relationships" : {
+                "webhookEvent" : { "data" : { "type" : "webhook-events", "id" : event["id"] } },
+            },
+        });
+
+        data.logs.entry(String::from(id)).or_default().insert(0, log);
+
+        Ok(json_response(reqwest::StatusCode::CREATED, json!({ "data" : event })))
+    }
+
+    fn list_webhook_logs(&self, id : &str) -> Result<Reply, Reply> {
+        let data = self.store.data.lock().unwrap();
+
+        if !data.webhooks.iter().any(|w| w["id"] == id) {
+            return Err(not_found());
+        }
+
+        let logs : Vec<&Value> = data.logs.get(id).map(|logs| logs.iter().collect()).unwrap_or_default();
+
+        self.page(&logs)
+    }
+
+    fn date_time_param(&self, name : &str) -> Result<Option<(i64, u32)>, Reply> {
+        match self.param(name) {
+            Some(value) => timestamp(&json!(value)).map(Some).ok_or_else(|| invalid_parameter(name)),
+            None => Ok(None),
+        }
+    }
+
+    /// Responds with one page of the resources, according to the `page[size]`, `page[after]` and `page[before]` parameters. The cursors are the IDs of the resources either side of the page.
+    fn page(&self, resources : &[&Value]) -> Result<Reply, Reply> {
+        let size = match self.param("page[size]") {
+            Some(value) => value.parse::<usize>().ok().filter(|s| (1..=100).contains(s)).ok_or_else(|| invalid_parameter("page[size]"))?,
+            None => DEFAULT_PAGE_SIZE,
+        };
+
+        let position = |name : &str| -> Result<Option<usize>, Reply> {
+            match self.param(name) {
+                Some(cursor) => resources.iter().position(|r| r["id"] == cursor.as_str()).map(Some).ok_or_else(|| invalid_parameter(name)),
+                None => Ok(None),
+            }
+        };
+
+        let (start, end) = match (position("page[after]")?, position("page[before]")?) {
+            (Some(after), _) => (after + 1, (after + 1 + size).min(resources.len())),
+            (None, Some(before)) => (before.saturating_sub(size), before),
+            (None, None) => (0, size.min(resources.len())),
+        };
+
+        let data : Vec<Value> = resources[start..end].iter().map(|r| with_links(r, self.base)).collect();
+
+        let link = |cursor : &str, id : &Value| -> Value {
+            let mut url = self.url.clone();
+            let pairs : Vec<(String, String)> =
+                self.url
+                .query_pairs()
+                .filter(|(k, _)| k != "page[after]" && k != "page[before]")
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect();
+
+            url.query_pairs_mut().clear().extend_pairs(pairs).append_pair(cursor, id.as_str().unwrap_or_default());
+            json!(url.as_str())
+        };
+
+        // A page past either end of the resources is empty, and has no link onwards from its missing first or last resource.
+        let first = resources.get(start).filter(|_| start > 0 && start < end);
+        let last = end.checked_sub(1).and_then(|i| resources.get(i)).filter(|_| start < end && end < resources.len());
+
+        let prev = first.map_or(Value::Null, |r| link("page[before]", &r["id"]));
+        let next = last.map_or(Value::Null, |r| link("page[after]", &r["id"]));
+
+        Ok(json_response(reqwest::StatusCode::OK, json!({ "data" : data, "links" : { "prev" : prev, "next" : next } })))
+    }
+}
+
+/// The labels of the tags of a transaction.
+fn tags_of(transaction : &Value) -> Vec<String> {
+    transaction["relationships"]["tags"]["data"]
+        .as_array()
+        .map(|tags| tags.iter().filter_map(|t| t["id"].as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Adds the canonical link of a resource, which depends on the address of the server.
+fn with_links(resource : &Value, base : &str) -> Value {
+    let mut resource = resource.clone();
+    let collection = resource["type"].as_str().unwrap_or_default().to_string();
+    let id = resource["id"].as_str().unwrap_or_default().to_string();
+
+    if collection != "tags" {
+        resource["links"] = json!({ "self" : format!("{}/{}/{}", base, collection, id) });
+    }
+
+    if collection == "webhooks" {
+        resource["relationships"]["logs"]["links"] = json!({ "related" : format!("{}/webhooks/{}/logs", base, id) });
+    }
+
+    resource
+}
+
+/// The current time, formatted as a date-time in UTC.
+fn now() -> String {
+    let seconds = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
+    standard::format_timestamp(seconds, 0)
+}
+
+/// The status and body of a response, which endpoints return as `Err` for errors such that they can use `?`.
+struct Reply {
+    status : reqwest::StatusCode,
+    body : Option<Value>,
+}
+
+fn json_response(status : reqwest::StatusCode, body : Value) -> Reply {
+    Reply {
+        status,
+        body : Some(body),
+    }
+}
+
+fn empty_response(status : reqwest::StatusCode) -> Reply {
+    Reply {
+        status,
+        body : None,
+    }
+}
+
+/// A JSON:API error document, as returned by the API.
+fn error_response(status : reqwest::StatusCode, title : &str, detail : &str, source : Option<Value>) -> Reply {
+    let mut error = json!({
+        "status" : status.as_str(),
+        "title" : title,
+        "detail" : detail,
+    });
+
+    if let Some(source) = source {
+        error["source"] = source;
+    }
+
+    json_response(status, json!({ "errors" : [error] }))
+}
+
+fn not_found() -> Reply {
+    error_response(reqwest::StatusCode::NOT_FOUND, "Not Found", "The requested resource does not exist.", None)
+}
+
+fn invalid_parameter(name : &str) -> Reply {
+    error_response(reqwest::StatusCode::BAD_REQUEST, "Invalid Parameter", &format!("The value of the {} parameter is invalid.", name), Some(json!({ "parameter" : name })))
+}
+
+fn invalid_body() -> Reply {
+    error_response(reqwest::StatusCode::BAD_REQUEST, "Invalid Request Body", "The request body is not a valid JSON:API document.", Some(json!({ "pointer" : "/data" })))
+}
+
+// ----------------- Transport -----------------
+
+/// A `Transport` which responds to requests from a `Store`, without a network.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryTransport {
+    store : Store,
+}
+
+impl MemoryTransport {
+    /// Creates a transport which responds from (and modifies) the given store.
+    pub fn new(store : Store) -> Self {
+        MemoryTransport {
+            store
+        }
+    }
+}
+
+#[async_trait]
+impl transport::Transport for MemoryTransport {
+    async fn send(&self, request : transport::Request) -> Result<transport::Response, error::Error> {
+        Ok(self.store.respond(&request))
+    }
+}
+
+// ----------------- Implementation -----------------
+
+/// An in-memory implementation of `UpApi` over the data in a `Store`, which behaves as the API does (including pagination, filters and error responses) without a network.
+///
+/// ```
+/// use up_api::v1::api::UpApi;
+/// use up_api::v1::memory::{InMemoryApi, Store};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let api = InMemoryApi::new(Store::sample());
+///
+/// let accounts = api.list_accounts(&Default::default()).await.unwrap();
+/// assert_eq!(accounts.data[0].attributes.display_name, "Spending");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct InMemoryApi {
+    client : Client,
+    store : Store,
+}
+
+impl InMemoryApi {
+    /// Creates the implementation over the given store.
+    pub fn new(store : Store) -> Self {
+        let client =
+            Client::builder(String::from("up:yeah:in-memory"))
+            .transport(Arc::new(MemoryTransport::new(store.clone())))
+            .retry_policy(crate::v1::retry::RetryPolicy::none())
+            .build()
+            .expect("The default base URL is valid.");
+
+        InMemoryApi {
+            client,
+            store,
+        }
+    }
+
+    /// The store holding the data, which can be used to add data or inspect the effect of requests.
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// A `Client` over the same data, for the methods (such as `list_transactions_stream` and `Paginated::next`) which are not part of `UpApi`.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+crate::v1::api::delegate_up_api!(InMemoryApi, |self| &self.client);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::api::UpApi;
+    use crate::v1::{standard, transactions};
+
+    /// Code written against the trait, which can be given either implementation.
+    async fn spent(api : &dyn UpApi, account_id : &standard::AccountId) -> Result<i64, error::Error> {
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.filter_status(standard::TransactionStatusEnum::Settled).page_size(100);
+
+        let response = api.list_transactions_by_account(account_id, &options).await?;
+
+        Ok(response.data.iter().map(|t| t.attributes.amount.value_in_base_units).filter(|v| *v < 0).sum())
+    }
+
+    #[tokio::test]
+    async fn code_can_be_written_against_the_trait() {
+        let api = InMemoryApi::new(Store::sample());
+
+        assert_eq!(spent(&api, &standard::AccountId::from("spending")).await.unwrap(), -(6415 + 480 + 1250));
+        assert!(spent(&api, &standard::AccountId::from("missing")).await.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn changes_are_kept_in_the_store() {
+        let api = InMemoryApi::new(Store::sample());
+        let id = standard::TransactionId::from("transaction-2");
+
+        api.add_tags(&id, vec![standard::TagId::from("Commute")]).await.unwrap();
+        api.categorise_transaction(&id, None).await.unwrap();
+
+        let transaction = api.get_transaction(&id).await.unwrap().data;
+        assert_eq!(transaction.relationships.tags.data[0].id.as_str(), "Commute");
+        assert!(transaction.relationships.category.data.is_none());
+
+        let webhook = api.create_webhook("https://example.com/hook", None).await.unwrap().data;
+        api.ping_webhook(&webhook.id).await.unwrap();
+        assert_eq!(api.list_webhook_logs(&webhook.id, &Default::default()).await.unwrap().data.len(), 1);
+        assert_eq!(api.store().webhooks().len(), 1);
+    }
+
+    #[test]
+    fn pages_past_either_end_are_empty() {
+        let store = Store::sample();
+        let transactions = store.transactions();
+
+        let page = |cursor : &str, id : &Value| -> Value {
+            let mut url = reqwest::Url::parse("https://api.up.com.au/api/v1/transactions").unwrap();
+            url.query_pairs_mut().append_pair(cursor, id.as_str().unwrap());
+
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(reqwest::header::AUTHORIZATION, reqwest::header::HeaderValue::from_static("Bearer up:yeah:token"));
+
+            let response = store.respond(&transport::Request { method : reqwest::Method::GET, url, headers, body : None });
+            assert_eq!(response.status, reqwest::StatusCode::OK);
+            serde_json::from_str(&response.body).unwrap()
+        };
+
+        for (cursor, id) in [("page[before]", &transactions[0]["id"]), ("page[after]", &transactions[transactions.len() - 1]["id"])] {
+            let body = page(cursor, id);
+
+            assert_eq!(body["data"], json!([]), "{}", cursor);
+            assert_eq!(body["links"], json!({ "prev" : null, "next" : null }), "{}", cursor);
+        }
+
+        let body = page("page[before]", &transactions[1]["id"]);
+        assert_eq!(body["data"][0]["id"], transactions[0]["id"]);
+        assert!(body["links"]["next"].as_str().unwrap().contains("page%5Bafter%5D="));
+        assert_eq!(store.transactions().len(), transactions.len());
+    }
+
+    #[test]
+    fn category_names_are_derived_from_their_ids() {
+        assert_eq!(category("restaurants-and-cafes", None, &[])["attributes"]["name"], "Restaurants & Cafes");
+        assert_eq!(category("über--eats-", None, &[])["attributes"]["name"], "Über  Eats ");
+        assert_eq!(category("", None, &[])["attributes"]["name"], "");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::v1::error;
+use crate::v1::transport::{Request, Response, Transport};
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+/// Behaviour run around every request made by a `Client`, added with `ClientBuilder::middleware`.
+///
+/// Middleware is run in the order it was added, each seeing the request before (and the response after) the middleware added after it. This includes the requests made to follow the `prev` and `next` links of a page and each attempt made when retrying. A middleware can modify the request before passing it on with `next.run`, inspect or modify the response, or short-circuit the request by returning without calling `next.run` at all.
+///
+/// ```
+/// use up_api::v1::error::Error;
+/// use up_api::v1::middleware::{Middleware, Next};
+/// use up_api::v1::transport::{Request, Response};
+///
+/// struct RequestId;
+///
+/// #[async_trait::async_trait]
+/// impl Middleware for RequestId {
+///     async fn handle(&self, mut request : Request, next : Next<'_>) -> Result<Response, Error> {
+///         request.headers.insert("x-request-id", reqwest::header::HeaderValue::from_static("example"));
+///         next.run(request).await
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Middleware : Send + Sync {
+    /// Handles a request, typically by calling `next.run` to pass it on to the rest of the chain.
+    async fn handle(&self, request : Request, next : Next<'_>) -> Result<Response, error::Error>;
+}
+
+/// The remainder of a middleware chain, ending in the `Transport` of the `Client`.
+pub struct Next<'a> {
+    middleware : &'a [Arc<dyn Middleware>],
+    transport : &'a dyn Transport,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(middleware : &'a [Arc<dyn Middleware>], transport : &'a dyn Transport) -> Self {
+        Next {
+            middleware,
+            transport,
+        }
+    }
+
+    /// Passes the request on to the next middleware in the chain, or sends it if there is none left.
+    pub async fn run(self, request : Request) -> Result<Response, error::Error> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => first.handle(request, Next::new(rest, self.transport)).await,
+            None => self.transport.send(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::{Client, memory, pagination, test_util, transactions};
+
+    use std::sync::Mutex;
+
+    use futures::TryStreamExt;
+
+    #[derive(Default)]
+    struct Audit {
+        log : Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Audit {
+        async fn handle(&self, request : Request, next : Next<'_>) -> Result<Response, error::Error> {
+            let target = format!("{} {}", request.method, request.url.query().unwrap_or(""));
+            let response = next.run(request).await?;
+            self.log.lock().unwrap().push(format!("{} {}", target, response.status.as_u16()));
+            Ok(response)
+        }
+    }
+
+    /// Fails the first request it sees with a server error.
+    #[derive(Default)]
+    struct FailOnce {
+        failed : Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl Middleware for FailOnce {
+        async fn handle(&self, request : Request, next : Next<'_>) -> Result<Response, error::Error> {
+            if !std::mem::replace(&mut *self.failed.lock().unwrap(), true) {
+                return Ok(Response::empty(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+            }
+
+            next.run(request).await
+        }
+    }
+
+    struct Header;
+
+    #[async_trait]
+    impl Middleware for Header {
+        async fn handle(&self, mut request : Request, next : Next<'_>) -> Result<Response, error::Error> {
+            if request.headers.contains_key("x-checked") {
+                return Err(error::Error::Transport(String::from("Middleware ran twice.")));
+            }
+
+            request.headers.insert("x-checked", reqwest::header::HeaderValue::from_static("yes"));
+            next.run(request).await
+        }
+    }
+
+    fn client(middleware : Vec<Arc<dyn Middleware>>) -> Client {
+        let mut builder = test_util::client_builder(Arc::new(memory::MemoryTransport::new(memory::Store::sample())));
+
+        for value in middleware {
+            builder = builder.middleware(value);
+        }
+
+        builder.build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn pagination_and_retries_pass_through_the_chain() {
+        let audit = Arc::new(Audit::default());
+        let client = client(vec![audit.clone(), Arc::new(FailOnce::default()), Arc::new(Header)]);
+
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.page_size(3);
+
+        let transactions : Vec<_> = client.list_transactions_stream(&options, pagination::StreamLimits::default()).try_collect().await.unwrap();
+        assert_eq!(transactions.len(), 5);
+
+        let log = audit.log.lock().unwrap();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[0], "GET page%5Bsize%5D=3 503");
+        assert_eq!(log[1], "GET page%5Bsize%5D=3 200");
+        assert!(log[2].contains("page%5Bafter%5D=transaction-3"));
+    }
+
+    #[tokio::test]
+    async fn middleware_can_short_circuit() {
+        struct Offline;
+
+        #[async_trait]
+        impl Middleware for Offline {
+            async fn handle(&self, _request : Request, _next : Next<'_>) -> Result<Response, error::Error> {
+                Ok(Response::json(reqwest::StatusCode::OK, r#"{"meta":{"id":"offline","statusEmoji":"⚡️"}}"#))
+            }
+        }
+
+        let audit = Arc::new(Audit::default());
+        let client = client(vec![Arc::new(Offline), audit.clone()]);
+
+        assert_eq!(client.ping().await.unwrap().meta.id, "offline");
+        assert!(audit.log.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+use crate::v1::{error, transport};
+use crate::v1::transport::{Request, Response, Transport};
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+/// The path prefix of the default base URL, which is ignored when matching requests.
+const API_PREFIX : &str = "/api/v1";
+
+/// An in-memory `Transport` which returns canned responses, for testing code which uses a `Client` without a network.
+///
+/// Responses are registered against a method and path (relative to the API, such as `/accounts`) and optionally a query. When several responses are registered for the same request they are returned in order, with the last repeating for any further requests. Requests with no registered response fail with `Error::Transport`. Every request made is recorded, so that tests can then assert which requests were made.
+///
+/// ```
+/// use std::sync::Arc;
+/// use up_api::v1::Client;
+/// use up_api::v1::mock::MockTransport;
+/// use up_api::v1::transport::Response;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mock = Arc::new(MockTransport::new());
+/// mock.register(reqwest::Method::GET, "/util/ping", Response::json(reqwest::StatusCode::OK, r#"{"meta":{"id":"abc","statusEmoji":"⚡️"}}"#));
+///
+/// let client = Client::builder(String::from("up:yeah:token")).transport(mock.clone()).build().unwrap();
+///
+/// assert_eq!(client.ping().await.unwrap().meta.id, "abc");
+/// mock.assert_requested(reqwest::Method::GET, "/util/ping");
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    routes : Mutex<Vec<Route>>,
+    requests : Mutex<Vec<Request>>,
+}
+
+#[derive(Debug)]
+struct Route {
+    method : reqwest::Method,
+    path : String,
+    query : Option<Vec<(String, String)>>,
+    responses : VecDeque<Response>,
+}
+
+impl Route {
+    fn matches(&self, method : &reqwest::Method, path : &str, query : &[(String, String)]) -> bool {
+        self.method == method
+            && self.path == path
+            && self.query.as_ref().is_none_or(|expected| same_pairs(expected, query))
+    }
+
+    fn next_response(&mut self) -> Response {
+        if self.responses.len() > 1 {
+            self.responses.pop_front().unwrap()
+        }
+        else {
+            self.responses[0].clone()
+        }
+    }
+}
+
+impl MockTransport {
+    /// Creates a transport with no registered responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a response for requests with the given method and path, whatever their query.
+    pub fn register(&self, method : reqwest::Method, path : &str, response : Response) {
+        self.add_route(method, path, None, response);
+    }
+
+    /// Registers a response for requests with the given method, path and query parameters. The order of the parameters does not matter, and these routes take precedence over those registered without a query.
+    pub fn register_with_query(&self, method : reqwest::Method, path : &str, query : &[(&str, &str)], response : Response) {
+        let query = query.iter().map(|(k, v)| (String::from(*k), String::from(*v))).collect();
+        self.add_route(method, path, Some(query), response);
+    }
+
+    fn add_route(&self, method : reqwest::Method, path : &str, query : Option<Vec<(String, String)>>, response : Response) {
+        let mut routes = self.routes.lock().unwrap();
+        let path = normalise_path(path);
+
+        match routes.iter_mut().find(|r| r.method == method && r.path == path && r.query == query) {
+            Some(route) => route.responses.push_back(response),
+            None => routes.push(Route { method, path, query, responses : VecDeque::from([response]) }),
+        }
+    }
+
+    /// All of the requests made so far, in order.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The requests made so far with the given method and path.
+    pub fn requests_to(&self, method : reqwest::Method, path : &str) -> Vec<Request> {
+        let path = normalise_path(path);
+
+        self.requests()
+            .into_iter()
+            .filter(|r| r.method == method && normalise_path(r.url.path()) == path)
+            .collect()
+    }
+
+    /// Panics unless at least one request was made with the given method and path.
+    pub fn assert_requested(&self, method : reqwest::Method, path : &str) {
+        if self.requests_to(method.clone(), path).is_empty() {
+            let made : Vec<String> = self.requests().iter().map(|r| format!("{} {}", r.method, r.url)).collect();
+            panic!("Expected a {} request to {}, but the requests made were: {:?}", method, path, made);
+        }
+    }
+
+    /// Panics if any request was made with the given method and path.
+    pub fn assert_not_requested(&self, method : reqwest::Method, path : &str) {
+        let count = self.requests_to(method.clone(), path).len();
+
+        if count > 0 {
+            panic!("Expected no {} requests to {}, but {} were made.", method, path, count);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, request : Request) -> Result<transport::Response, error::Error> {
+        let path = normalise_path(request.url.path());
+        let query : Vec<(String, String)> = request.url.query_pairs().into_owned().collect();
+
+        self.requests.lock().unwrap().push(request.clone());
+
+        let mut routes = self.routes.lock().unwrap();
+
+        let index =
+            routes
+            .iter()
+            .position(|r| r.query.is_some() && r.matches(&request.method, &path, &query))
+            .or_else(|| routes.iter().position(|r| r.matches(&request.method, &path, &query)));
+
+        match index {
+            Some(index) => Ok(routes[index].next_response()),
+            None => Err(error::Error::Transport(format!("No mock response is registered for {} {}", request.method, request.url))),
+        }
+    }
+}
+
+/// Strips the path prefix of the default base URL, and any trailing slash.
+fn normalise_path(path : &str) -> String {
+    let path = path.strip_prefix(API_PREFIX).unwrap_or(path);
+    let path = path.trim_end_matches('/');
+
+    if path.starts_with('/') { String::from(path) } else { format!("/{}", path) }
+}
+
+/// Whether two lists of query parameters contain the same parameters, in any order.
+fn same_pairs(expected : &[(String, String)], actual : &[(String, String)]) -> bool {
+    let mut expected = expected.to_vec();
+    let mut actual = actual.to_vec();
+    expected.sort();
+    actual.sort();
+    expected == actual
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::{accounts, pagination};
+    use crate::v1::test_util::client;
+
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+
+    fn accounts_page(ids : &[&str], next : Option<&str>) -> String {
+        let data : Vec<String> = ids.iter().map(|id| format!(r#"{{
+            "type": "accounts",
+            "id": "{}",
+            "attributes": {{
+                "displayName": "Spending",
+                "accountType": "TRANSACTIONAL",
+                "ownershipType": "INDIVIDUAL",
+                "balance": {{ "currencyCode": "AUD", "value": "1.00", "valueInBaseUnits": 100 }},
+                "createdAt": "2022-01-01T00:00:00+10:00"
+            }},
+            "relationships": {{ "transactions": {{}} }}
+        }}"#, id)).collect();
+
+        let next = next.map(|n| format!("\"{}\"", n)).unwrap_or(String::from("null"));
+
+        format!(r#"{{ "data": [{}], "links": {{ "prev": null, "next": {} }} }}"#, data.join(","), next)
+    }
+
+    #[tokio::test]
+    async fn requests_are_recorded_with_the_access_token() {
+        let mock = Arc::new(MockTransport::new());
+        mock.register(reqwest::Method::GET, "/accounts", Response::json(reqwest::StatusCode::OK, accounts_page(&["a"], None)));
+
+        let options = {
+            let mut options = accounts::ListAccountsOptions::default();
+            options.page_size(10);
+            options
+        };
+
+        let response = client(mock.clone()).list_accounts(&options).await.unwrap();
+        assert_eq!(response.data[0].id.as_str(), "a");
+
+        let requests = mock.requests_to(reqwest::Method::GET, "/accounts");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].headers[reqwest::header::AUTHORIZATION], "Bearer up:yeah:token");
+        assert_eq!(requests[0].url.query(), Some("page%5Bsize%5D=10"));
+        mock.assert_not_requested(reqwest::Method::GET, "/util/ping");
+    }
+
+    #[tokio::test]
+    async fn routes_with_a_query_take_precedence() {
+        let mock = Arc::new(MockTransport::new());
+        mock.register(reqwest::Method::GET, "/accounts", Response::json(reqwest::StatusCode::OK, accounts_page(&["any"], None)));
+        mock.register_with_query(reqwest::Method::GET, "/accounts", &[("page[size]", "1")], Response::json(reqwest::StatusCode::OK, accounts_page(&["one"], None)));
+
+        let client = client(mock.clone());
+        let mut options = accounts::ListAccountsOptions::default();
+
+        assert_eq!(client.list_accounts(&options).await.unwrap().data[0].id.as_str(), "any");
+        options.page_size(1);
+        assert_eq!(client.list_accounts(&options).await.unwrap().data[0].id.as_str(), "one");
+    }
+
+    #[tokio::test]
+    async fn retryable_responses_are_retried() {
+        let mock = Arc::new(MockTransport::new());
+        mock.register(reqwest::Method::GET, "/accounts", Response::empty(reqwest::StatusCode::TOO_MANY_REQUESTS).with_header(reqwest::header::RETRY_AFTER, reqwest::header::HeaderValue::from_static("0")));
+        mock.register(reqwest::Method::GET, "/accounts", Response::empty(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+        mock.register(reqwest::Method::GET, "/accounts", Response::json(reqwest::StatusCode::OK, accounts_page(&["a"], None)));
+
+        let response = client(mock.clone()).list_accounts(&accounts::ListAccountsOptions::default()).await.unwrap();
+
+        assert_eq!(response.data.len(), 1);
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn error_responses_are_classified() {
+        let mock = Arc::new(MockTransport::new());
+        mock.register(reqwest::Method::GET, "/accounts/missing", Response::json(reqwest::StatusCode::NOT_FOUND, r#"{
+            "errors": [{ "status": "404", "title": "Not Found", "detail": "The account was not found." }]
+        }"#));
+
+        let error = client(mock.clone()).get_account(&"missing".into()).await.unwrap_err();
+
+        assert!(error.is_not_found());
+        assert_eq!(error.api_errors()[0].title, "Not Found");
+
+        let error = client(mock.clone()).ping().await.unwrap_err();
+        assert!(matches!(error, error::Error::Transport(_)));
+    }
+
+    #[tokio::test]
+    async fn streams_follow_next_links() {
+        let mock = Arc::new(MockTransport::new());
+        mock.register_with_query(reqwest::Method::GET, "/accounts", &[("page[after]", "2")], Response::json(reqwest::StatusCode::OK, accounts_page(&["c"], None)));
+        mock.register(reqwest::Method::GET, "/accounts", Response::json(reqwest::StatusCode::OK, accounts_page(&["a", "b"], Some("https://api.up.com.au/api/v1/accounts?page%5Bafter%5D=2"))));
+
+        let ids : Vec<String> =
+            client(mock.clone())
+            .list_accounts_stream(&accounts::ListAccountsOptions::default(), pagination::StreamLimits::default())
+            .map_ok(|account| account.id.into_inner())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(mock.requests().len(), 2);
+    }
+}
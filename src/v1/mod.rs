@@ -1,6 +1,3 @@
-#[macro_use]
-mod macros;
-
 /// Error types and trait implementations.
 pub mod error;
 /// Types for modelling and interacting with [accounts](https://developer.up.com.au/#accounts).
@@ -17,24 +14,313 @@ pub mod utilities;
 pub mod webhooks;
 /// Types which are stardized (and named) across many resources.
 pub mod standard;
+/// A decimal-safe representation of amounts of money, with checked arithmetic.
+pub mod money;
+/// Types for following the links between pages of list responses.
+pub mod pagination;
+/// Configuration for retrying requests which fail due to rate limiting or transient errors.
+pub mod retry;
+/// The interface through which a `Client` sends HTTP requests, allowing the network to be replaced.
+pub mod transport;
+/// Hooks which run around every request, such as to add headers, record metrics or inject faults.
+pub mod middleware;
+/// An in-memory transport returning canned responses, for testing code which uses a `Client`, enabled with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
+/// Transports which record a session with the API to a file and replay it offline, enabled with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub mod cassette;
+/// A trait over the endpoints of the API, so that code can be written against either a `Client` or a fake.
+pub mod api;
+/// An in-memory implementation of the API, over data which can be seeded and inspected, enabled with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+/// A seeded generator of realistic accounts and transaction histories, for demos and tests, enabled with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub mod generator;
+/// A local HTTP server implementing the API over in-memory data, enabled with the `test-server` feature.
+#[cfg(feature = "test-server")]
+pub mod test_server;
+/// A synchronous client wrapping the async `Client`, enabled with the `blocking` feature.
+#[cfg(feature = "blocking")]
+pub mod blocking;
+/// A server receiving webhook events and dispatching them to a handler, enabled with the `webhook-server` feature.
+#[cfg(feature = "webhook-server")]
+pub mod webhook_server;
+/// Stores of processed webhook event IDs, used to skip redelivered events, enabled with the `webhook-server` feature.
+#[cfg(feature = "webhook-server")]
+pub mod event_store;
+#[cfg(feature = "tracing")]
+mod instrument;
+#[cfg(any(test, feature = "testing", feature = "tracing"))]
+mod scrub;
+#[cfg(test)]
+mod test_util;
 
+use std::sync::Arc;
+use std::time::Duration;
 
 static BASE_URL : &str = "https://api.up.com.au/api/v1";
 
 /// A client for interacting with the Up API.
+#[derive(Clone)]
 pub struct Client {
     access_token : String,
+    base_url : String,
+    transport : Arc<dyn transport::Transport>,
+    middleware : Arc<[Arc<dyn middleware::Middleware>]>,
+    retry : retry::RetryPolicy,
 }
 
 impl Client {
     /// Creates an instance of the `Client` from the access token. Visit [this page](https://api.up.com.au/getting_started) to get such a token.
     pub fn new(access_token : String) -> Self {
         Client {
-            access_token
+            access_token,
+            base_url : String::from(BASE_URL),
+            transport : Arc::new(transport::ReqwestTransport::default()),
+            middleware : Arc::new([]),
+            retry : retry::RetryPolicy::default(),
         }
     }
 
+    /// Creates a `ClientBuilder` from the access token, which can be used to configure the `Client` before it is created.
+    pub fn builder(access_token : String) -> ClientBuilder {
+        ClientBuilder::new(access_token)
+    }
+
     fn auth_header(&self) -> String {
         format!("Bearer {}", self.access_token)
     }
+
+    /// Sends a request to the API, retrying according to the client's `RetryPolicy`. If a body is provided it is sent as JSON.
+    async fn send(&self, method : reqwest::Method, url : reqwest::Url, body : Option<String>) -> Result<transport::Response, error::Error> {
+        self.send_page(method, url, body, None).await
+    }
+
+    /// Sends a request as with `send`, where the request is known to be for the given page of a list (counting from 1). This is only used to annotate the request when tracing.
+    async fn send_page(&self, method : reqwest::Method, url : reqwest::Url, body : Option<String>, page : Option<usize>) -> Result<transport::Response, error::Error> {
+        #[cfg(feature = "tracing")]
+        {
+            let span = instrument::request_span(&method, &url, page);
+            tracing::Instrument::instrument(self.send_with_retries(method, url, body), span).await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = page;
+            self.send_with_retries(method, url, body).await
+        }
+    }
+
+    async fn send_with_retries(&self, method : reqwest::Method, url : reqwest::Url, body : Option<String>) -> Result<transport::Response, error::Error> {
+        #[cfg(feature = "tracing")]
+        let started = std::time::Instant::now();
+
+        let retryable = self.retry.applies_to(&method);
+        let mut attempt = 1;
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        let auth = reqwest::header::HeaderValue::from_str(&self.auth_header())
+            .map_err(|_| error::Error::InvalidArgument(String::from("The access token must only contain visible ASCII characters.")))?;
+        headers.insert(reqwest::header::AUTHORIZATION, auth);
+
+        if body.is_some() {
+            headers.insert(reqwest::header::CONTENT_TYPE, reqwest::header::HeaderValue::from_static("application/json"));
+        }
+
+        loop {
+            let request = transport::Request {
+                method : method.clone(),
+                url : url.clone(),
+                headers : headers.clone(),
+                body : body.clone(),
+            };
+
+            let result = middleware::Next::new(&self.middleware, self.transport.as_ref()).run(request).await;
+
+            #[cfg(feature = "tracing")]
+            instrument::record_attempt(&self.access_token, body.as_deref(), &result, attempt, started.elapsed());
+
+            let delay = match &result {
+                Ok(res) if retry::is_retryable_status(res.status) => {
+                    self.retry.delay(attempt, retry::retry_after(&res.headers))
+                },
+                Err(error::Error::Request(err)) if retry::is_retryable_error(err) => {
+                    self.retry.delay(attempt, None)
+                },
+                _ => None,
+            };
+
+            match delay {
+                Some(delay) if retryable && self.retry.allows_attempt(attempt) => {
+                    #[cfg(feature = "tracing")]
+                    instrument::record_retry(attempt, delay);
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                },
+                _ => return result,
+            }
+        }
+    }
+}
+
+/// Checks that an ID can be used as a segment of a request path. Without this an empty ID would be
+/// treated as a request for many resources, and an ID containing a separator as a request for
+/// some other endpoint, both of which produce very unclear errors.
+fn validate_id(description : &str, id : &str) -> Result<(), error::Error> {
+    if id.is_empty() {
+        Err(error::Error::InvalidArgument(format!("The provided {} must not be empty.", description)))
+    }
+    else if id.contains(['/', '\\', '?', '#']) || id == "." || id == ".." {
+        Err(error::Error::InvalidArgument(format!("The provided {} ({:?}) must not contain path separators.", description, id)))
+    }
+    else {
+        Ok(())
+    }
+}
+
+/// A builder for configuring a `Client`. The underlying HTTP connection pool is created once when `build` is called and shared by every request made through the resulting `Client`.
+pub struct ClientBuilder {
+    access_token : String,
+    base_url : String,
+    user_agent : Option<String>,
+    connect_timeout : Option<Duration>,
+    timeout : Option<Duration>,
+    proxy : Option<reqwest::Proxy>,
+    retry : retry::RetryPolicy,
+    transport : Option<Arc<dyn transport::Transport>>,
+    middleware : Vec<Arc<dyn middleware::Middleware>>,
+}
+
+impl ClientBuilder {
+    /// Creates a `ClientBuilder` from the access token, with all other settings left as their defaults.
+    pub fn new(access_token : String) -> Self {
+        ClientBuilder {
+            access_token,
+            base_url : String::from(BASE_URL),
+            user_agent : None,
+            connect_timeout : None,
+            timeout : None,
+            proxy : None,
+            retry : retry::RetryPolicy::default(),
+            transport : None,
+            middleware : Vec::new(),
+        }
+    }
+
+    /// Sets the base URL which all endpoints are relative to. Defaults to `https://api.up.com.au/api/v1`, and is mostly useful for pointing the client at a local stand-in server.
+    pub fn base_url(mut self, value : &str) -> Self {
+        self.base_url = String::from(value.trim_end_matches('/'));
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, value : &str) -> Self {
+        self.user_agent = Some(String::from(value));
+        self
+    }
+
+    /// Sets the timeout for establishing a connection to the API.
+    pub fn connect_timeout(mut self, value : Duration) -> Self {
+        self.connect_timeout = Some(value);
+        self
+    }
+
+    /// Sets the timeout for each request, from when the connection is started until the response body has been read.
+    pub fn timeout(mut self, value : Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    /// Sets a proxy through which all requests are made.
+    pub fn proxy(mut self, value : reqwest::Proxy) -> Self {
+        self.proxy = Some(value);
+        self
+    }
+
+    /// Sets the policy used to retry requests which fail due to rate limiting, server errors or transient connection problems.
+    pub fn retry_policy(mut self, value : retry::RetryPolicy) -> Self {
+        self.retry = value;
+        self
+    }
+
+    /// Sets the transport through which requests are sent, such as a `MockTransport` in tests. When set, the `user_agent`, `connect_timeout`, `timeout` and `proxy` settings are ignored, as they only configure the default transport.
+    pub fn transport(mut self, value : Arc<dyn transport::Transport>) -> Self {
+        self.transport = Some(value);
+        self
+    }
+
+    /// Adds a middleware to the end of the chain run around every request. Middleware runs in the order it is added, with the first added seeing each request first and each response last.
+    pub fn middleware(mut self, value : Arc<dyn middleware::Middleware>) -> Self {
+        self.middleware.push(value);
+        self
+    }
+
+    /// Creates the `Client`, failing if the base URL is invalid or the HTTP client could not be initialised.
+    pub fn build(self) -> Result<Client, error::Error> {
+        reqwest::Url::parse(&self.base_url).map_err(error::Error::UrlParse)?;
+
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut http = reqwest::Client::builder();
+
+                if let Some(value) = self.user_agent {
+                    http = http.user_agent(value);
+                }
+
+                if let Some(value) = self.connect_timeout {
+                    http = http.connect_timeout(value);
+                }
+
+                if let Some(value) = self.timeout {
+                    http = http.timeout(value);
+                }
+
+                if let Some(value) = self.proxy {
+                    http = http.proxy(value);
+                }
+
+                let http = http.build().map_err(error::Error::Request)?;
+
+                Arc::new(transport::ReqwestTransport::new(http))
+            },
+        };
+
+        Ok(Client {
+            access_token : self.access_token,
+            base_url : self.base_url,
+            transport,
+            middleware : self.middleware.into(),
+            retry : self.retry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_must_be_path_segments() {
+        assert!(validate_id("account ID", "2d3f4e5a-1b2c-4d5e-8f9a-0b1c2d3e4f5a").is_ok());
+
+        for id in ["", "a/b", "a\\b", "a?b", "a#b", ".", ".."] {
+            assert!(matches!(validate_id("account ID", id), Err(error::Error::InvalidArgument(_))), "{}", id);
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_arguments_are_errors_rather_than_panics() {
+        let client = Client::new(String::from("up:yeah:token"));
+
+        let result = client.get_account(&standard::AccountId::from("")).await;
+        assert!(matches!(result, Err(error::Error::InvalidArgument(_))));
+
+        let tags = (0..7).map(|i| standard::TagId::from(format!("tag {}", i))).collect();
+        let result = client.add_tags(&standard::TransactionId::from("abc"), tags).await;
+        assert!(matches!(result, Err(error::Error::InvalidArgument(_))));
+    }
 }
@@ -0,0 +1,368 @@
+use crate::v1::standard::MoneyObject;
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// An amount of money in a given currency, stored as an integer number of the smallest denomination of that currency so that arithmetic never loses cents.
+///
+/// ```
+/// use up_api::v1::money::Money;
+///
+/// let coffee = Money::parse("-4.50", "AUD").unwrap();
+/// let refund = Money::new(450, "AUD");
+///
+/// assert!(coffee.checked_add(&refund).unwrap().is_zero());
+/// assert_eq!(coffee.to_string(), "-$4.50");
+/// assert!(coffee.checked_add(&Money::new(100, "USD")).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Money {
+    value_in_base_units : i64,
+    currency_code : String,
+}
+
+impl Money {
+    /// Creates an amount from a number of the smallest denomination of the currency (for example cents for Australian dollars) and its ISO 4217 currency code.
+    pub fn new(value_in_base_units : i64, currency_code : &str) -> Self {
+        Money {
+            value_in_base_units,
+            currency_code : currency_code.to_ascii_uppercase(),
+        }
+    }
+
+    /// Creates an amount of zero in the given currency.
+    pub fn zero(currency_code : &str) -> Self {
+        Self::new(0, currency_code)
+    }
+
+    /// Parses an amount formatted as in the `value` field of a `MoneyObject` (for example `"-10.56"`). A currency symbol is also accepted, such that the output of `Display` can be parsed back.
+    pub fn parse(value : &str, currency_code : &str) -> Result<Self, MoneyError> {
+        let invalid = || MoneyError::Parse(String::from(value));
+        let currency_code = currency_code.to_ascii_uppercase();
+        let exponent = minor_units(&currency_code);
+
+        let mut rest = value.trim();
+        let suffix = format!(" {}", currency_code);
+        rest = rest.strip_suffix(suffix.as_str()).unwrap_or(rest);
+
+        let negative = rest.starts_with('-');
+        rest = rest.strip_prefix(['-', '+']).unwrap_or(rest);
+
+        if let Some(symbol) = symbol(&currency_code) {
+            rest = rest.strip_prefix(symbol).unwrap_or(rest);
+        }
+
+        let (whole, fraction) = match rest.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (rest, ""),
+        };
+
+        if !is_grouped(whole) {
+            return Err(invalid());
+        }
+
+        let whole = whole.replace(',', "");
+
+        if whole.is_empty()
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+            || fraction.len() > exponent as usize
+            || (exponent == 0 && rest.contains('.')) {
+            return Err(invalid());
+        }
+
+        let scale = 10i64.pow(exponent);
+        let fraction = format!("{:0<width$}", fraction, width = exponent as usize);
+        let fraction : i64 = if fraction.is_empty() { 0 } else { fraction.parse().map_err(|_| invalid())? };
+        let whole : i64 = whole.parse().map_err(|_| MoneyError::Overflow)?;
+
+        let magnitude =
+            whole
+            .checked_mul(scale)
+            .and_then(|w| w.checked_add(fraction))
+            .ok_or(MoneyError::Overflow)?;
+
+        Ok(Money {
+            value_in_base_units : if negative { -magnitude } else { magnitude },
+            currency_code,
+        })
+    }
+
+    /// The amount in the smallest denomination of the currency.
+    pub fn value_in_base_units(&self) -> i64 {
+        self.value_in_base_units
+    }
+
+    /// The ISO 4217 currency code.
+    pub fn currency_code(&self) -> &str {
+        &self.currency_code
+    }
+
+    /// Whether the amount is zero.
+    pub fn is_zero(&self) -> bool {
+        self.value_in_base_units == 0
+    }
+
+    /// Whether the amount is greater than zero, such as for incoming transactions.
+    pub fn is_positive(&self) -> bool {
+        self.value_in_base_units > 0
+    }
+
+    /// Whether the amount is less than zero, such as for outgoing transactions.
+    pub fn is_negative(&self) -> bool {
+        self.value_in_base_units < 0
+    }
+
+    /// Adds two amounts, failing if the currencies differ or the result overflows.
+    pub fn checked_add(&self, other : &Money) -> Result<Money, MoneyError> {
+        self.check_currency(other)?;
+
+        self.value_in_base_units
+            .checked_add(other.value_in_base_units)
+            .map(|value| Money::new(value, &self.currency_code))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Subtracts `other` from this amount, failing if the currencies differ or the result overflows.
+    pub fn checked_sub(&self, other : &Money) -> Result<Money, MoneyError> {
+        self.check_currency(other)?;
+
+        self.value_in_base_units
+            .checked_sub(other.value_in_base_units)
+            .map(|value| Money::new(value, &self.currency_code))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Negates the amount, failing if the result overflows.
+    pub fn checked_neg(&self) -> Result<Money, MoneyError> {
+        self.value_in_base_units
+            .checked_neg()
+            .map(|value| Money::new(value, &self.currency_code))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Sums an iterator of amounts, all of which must be in the given currency. An empty iterator sums to zero.
+    pub fn try_sum<I, M>(currency_code : &str, amounts : I) -> Result<Money, MoneyError>
+    where
+        I : IntoIterator<Item = M>,
+        M : std::borrow::Borrow<Money>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency_code), |total, amount| total.checked_add(amount.borrow()))
+    }
+
+    fn check_currency(&self, other : &Money) -> Result<(), MoneyError> {
+        if self.currency_code == other.currency_code {
+            Ok(())
+        }
+        else {
+            Err(MoneyError::CurrencyMismatch(self.currency_code.clone(), other.currency_code.clone()))
+        }
+    }
+}
+
+impl PartialOrd for Money {
+    /// Amounts are only comparable when they are in the same currency.
+    fn partial_cmp(&self, other : &Self) -> Option<Ordering> {
+        if self.currency_code == other.currency_code {
+            Some(self.value_in_base_units.cmp(&other.value_in_base_units))
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let exponent = minor_units(&self.currency_code);
+        let scale = 10u64.pow(exponent);
+        let magnitude = self.value_in_base_units.unsigned_abs();
+        let sign = if self.is_negative() { "-" } else { "" };
+
+        let number = if exponent == 0 {
+            format!("{}", magnitude)
+        }
+        else {
+            format!("{}.{:0width$}", magnitude / scale, magnitude % scale, width = exponent as usize)
+        };
+
+        match symbol(&self.currency_code) {
+            Some(symbol) => write!(f, "{}{}{}", sign, symbol, number),
+            None => write!(f, "{}{} {}", sign, number, self.currency_code),
+        }
+    }
+}
+
+impl From<&MoneyObject> for Money {
+    fn from(value : &MoneyObject) -> Self {
+        Money::new(value.value_in_base_units, &value.currency_code)
+    }
+}
+
+impl From<MoneyObject> for Money {
+    fn from(value : MoneyObject) -> Self {
+        Money {
+            value_in_base_units : value.value_in_base_units,
+            currency_code : value.currency_code.to_ascii_uppercase(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error type for arithmetic and parsing of `Money`.
+pub enum MoneyError {
+    /// Represents an operation between amounts in two different currencies.
+    CurrencyMismatch(String, String),
+    /// Represents an operation whose result does not fit in 64 bits.
+    Overflow,
+    /// Represents a string which could not be parsed as an amount of the given currency.
+    Parse(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::CurrencyMismatch(left, right) => write!(f, "Cannot combine amounts in different currencies: {} and {}", left, right),
+            Self::Overflow => write!(f, "The result of the operation is too large to be represented"),
+            Self::Parse(val) => write!(f, "Failed to parse the amount: {:?}", val),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+/// Whether the commas in the whole part of an amount (if any) separate it into groups of three digits, as in `"1,234,567"`.
+fn is_grouped(whole : &str) -> bool {
+    let mut groups = whole.split(',');
+    let first = groups.next().unwrap_or("");
+
+    !whole.contains(',') || ((1..=3).contains(&first.len()) && groups.all(|group| group.len() == 3))
+}
+
+/// The number of digits after the decimal point used by the currency, according to ISO 4217.
+fn minor_units(currency_code : &str) -> u32 {
+    match currency_code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// The symbol commonly used for the currency, if one is unambiguous enough to display.
+fn symbol(currency_code : &str) -> Option<&'static str> {
+    match currency_code {
+        "AUD" | "USD" | "NZD" | "CAD" | "SGD" | "HKD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "JPY" => Some("¥"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_are_parsed() {
+        let cases = [
+            ("10.56", "AUD", 1056),
+            ("-10.56", "aud", -1056),
+            ("+10.5", "AUD", 1050),
+            ("-$4.50", "AUD", -450),
+            ("$1,234,567.89", "AUD", 123456789),
+            ("7", "AUD", 700),
+            ("1,234 JPY", "JPY", 1234),
+            ("¥500", "JPY", 500),
+            ("1.234", "KWD", 1234),
+            ("12.34 CHF", "CHF", 1234),
+            (" 0.01 ", "AUD", 1),
+        ];
+
+        for (value, currency, expected) in cases {
+            assert_eq!(Money::parse(value, currency).map(|m| m.value_in_base_units()), Ok(expected), "{}", value);
+        }
+
+        assert_eq!(Money::parse("10.56", "aud").unwrap().currency_code(), "AUD");
+    }
+
+    #[test]
+    fn malformed_amounts_are_rejected() {
+        let cases = [
+            ("", "AUD"),
+            ("-", "AUD"),
+            (".50", "AUD"),
+            ("1.234", "AUD"),
+            ("1.5", "JPY"),
+            ("1.", "JPY"),
+            ("1,,2", "AUD"),
+            ("1,2", "AUD"),
+            (",123", "AUD"),
+            ("1234,567", "AUD"),
+            ("1,234,56", "AUD"),
+            ("1e3", "AUD"),
+            ("--1", "AUD"),
+            ("€5", "AUD"),
+            ("5 USD", "AUD"),
+        ];
+
+        for (value, currency) in cases {
+            assert_eq!(Money::parse(value, currency), Err(MoneyError::Parse(String::from(value))), "{}", value);
+        }
+
+        assert_eq!(Money::parse("92233720368547758.08", "AUD"), Err(MoneyError::Overflow));
+        assert_eq!(Money::parse("99999999999999999999", "JPY"), Err(MoneyError::Overflow));
+        assert_eq!(Money::parse("92233720368547758.07", "AUD").unwrap().value_in_base_units(), i64::MAX);
+    }
+
+    #[test]
+    fn arithmetic_is_checked() {
+        let max = Money::new(i64::MAX, "AUD");
+        let min = Money::new(i64::MIN, "AUD");
+        let one = Money::new(1, "AUD");
+
+        assert_eq!(max.checked_add(&one), Err(MoneyError::Overflow));
+        assert_eq!(min.checked_sub(&one), Err(MoneyError::Overflow));
+        assert_eq!(min.checked_neg(), Err(MoneyError::Overflow));
+        assert_eq!(max.checked_neg().unwrap().value_in_base_units(), -i64::MAX);
+        assert_eq!(max.checked_sub(&one).unwrap().value_in_base_units(), i64::MAX - 1);
+
+        let amounts = vec![Money::new(250, "AUD"), Money::new(-100, "AUD")];
+        assert_eq!(Money::try_sum("AUD", &amounts), Ok(Money::new(150, "AUD")));
+        assert_eq!(Money::try_sum("AUD", Vec::<Money>::new()), Ok(Money::zero("AUD")));
+        assert_eq!(Money::try_sum("AUD", [max.clone(), one.clone()]), Err(MoneyError::Overflow));
+
+        let mixed = vec![Money::new(250, "AUD"), Money::new(100, "USD")];
+        assert_eq!(Money::try_sum("AUD", &mixed), Err(MoneyError::CurrencyMismatch(String::from("AUD"), String::from("USD"))));
+        assert_eq!(Money::try_sum("USD", &amounts), Err(MoneyError::CurrencyMismatch(String::from("USD"), String::from("AUD"))));
+    }
+
+    #[test]
+    fn only_amounts_in_the_same_currency_are_ordered() {
+        let small = Money::new(100, "AUD");
+        let large = Money::new(200, "AUD");
+        let foreign = Money::new(100, "USD");
+
+        assert!(small < large);
+        assert_eq!(small.partial_cmp(&Money::new(100, "aud")), Some(Ordering::Equal));
+        assert_eq!(small.partial_cmp(&foreign), None);
+        assert_eq!(foreign.partial_cmp(&small), None);
+        assert_ne!(small, foreign);
+    }
+
+    #[test]
+    fn amounts_are_displayed() {
+        assert_eq!(Money::new(-450, "AUD").to_string(), "-$4.50");
+        assert_eq!(Money::new(5, "GBP").to_string(), "£0.05");
+        assert_eq!(Money::new(1500, "JPY").to_string(), "¥1500");
+        assert_eq!(Money::new(-1234, "CHF").to_string(), "-12.34 CHF");
+        assert_eq!(Money::new(1234, "KWD").to_string(), "1.234 KWD");
+        assert_eq!(Money::new(12, "KRW").to_string(), "12 KRW");
+
+        for money in [Money::new(-1234, "CHF"), Money::new(12, "KRW"), Money::new(-450, "AUD")] {
+            assert_eq!(Money::parse(&money.to_string(), money.currency_code()), Ok(money.clone()));
+        }
+    }
+}
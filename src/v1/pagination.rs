@@ -0,0 +1,245 @@
+use crate::v1::{Client, error};
+
+use std::collections::VecDeque;
+use std::future::Future;
+
+use futures::Stream;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+#[derive(Deserialize, Debug)]
+pub struct PageLinks {
+    /// The link to the previous page in the results. If this value is `None` there is no previous page.
+    pub prev : Option<String>,
+    /// The link to the next page in the results. If this value is `None` there is no next page.
+    pub next : Option<String>,
+}
+
+/// A single page of a paginated list response, which can be used to write code that works over any of the list responses of the API.
+pub trait Paginated : DeserializeOwned + Send + Sync {
+    /// The type of resource contained in each page.
+    type Item : Send;
+
+    /// The resources returned in this page.
+    fn data(&self) -> &[Self::Item];
+
+    /// The links to the adjacent pages.
+    fn links(&self) -> &PageLinks;
+
+    /// Consumes the page, returning the resources it contains.
+    fn into_data(self) -> Vec<Self::Item>;
+
+    /// Follows the link to the next page, returns None of the next page does not exist.
+    fn next(&self, client : &Client) -> impl Future<Output = Option<Result<Self, error::Error>>> + Send {
+        async move {
+            match self.links().next.as_ref() {
+                Some(url) => Some(follow_link(client, url, None).await),
+                None => None,
+            }
+        }
+    }
+
+    /// Follows the link to the previous page, returns None of the previous page does not exist.
+    fn prev(&self, client : &Client) -> impl Future<Output = Option<Result<Self, error::Error>>> + Send {
+        async move {
+            match self.links().prev.as_ref() {
+                Some(url) => Some(follow_link(client, url, None).await),
+                None => None,
+            }
+        }
+    }
+}
+
+/// Implements `Paginated` for a list response with a `data` list and `links`, along with inherent `next` and `prev` methods which forward to those of the trait, so they can be called without importing it.
+macro_rules! implement_paginated {
+    ($type:ty, $item:ty) => {
+        impl crate::v1::pagination::Paginated for $type {
+            type Item = $item;
+
+            fn data(&self) -> &[Self::Item] {
+                &self.data
+            }
+
+            fn links(&self) -> &crate::v1::pagination::PageLinks {
+                &self.links
+            }
+
+            fn into_data(self) -> Vec<Self::Item> {
+                self.data
+            }
+        }
+
+        impl $type {
+            /// Follows the link to the next page, returns None of the next page does not exist.
+            pub async fn next(&self, client : &crate::v1::Client) -> Option<Result<Self, crate::v1::error::Error>> {
+                crate::v1::pagination::Paginated::next(self, client).await
+            }
+
+            /// Follows the link to the previous page, returns None of the previous page does not exist.
+            pub async fn prev(&self, client : &crate::v1::Client) -> Option<Result<Self, crate::v1::error::Error>> {
+                crate::v1::pagination::Paginated::prev(self, client).await
+            }
+        }
+    };
+}
+
+pub(crate) use implement_paginated;
+
+/// Limits on how much of a paginated list is retrieved by one of the `list_*_stream` methods. By default the stream continues until the last page has been read.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StreamLimits {
+    /// The maximum number of resources the stream will yield.
+    max_items : Option<usize>,
+    /// The maximum number of pages the stream will request.
+    max_pages : Option<usize>,
+}
+
+impl StreamLimits {
+    /// Sets the maximum number of resources the stream will yield.
+    pub fn max_items(mut self, value : usize) -> Self {
+        self.max_items = Some(value);
+        self
+    }
+
+    /// Sets the maximum number of pages the stream will request.
+    pub fn max_pages(mut self, value : usize) -> Self {
+        self.max_pages = Some(value);
+        self
+    }
+}
+
+/// The largest page size accepted by the API.
+pub const MAX_PAGE_SIZE : u8 = 100;
+
+/// Checks that a page size is within the range accepted by the API.
+pub(crate) fn validate_page_size(value : u8) -> Result<(), error::Error> {
+    if (1..=MAX_PAGE_SIZE).contains(&value) {
+        Ok(())
+    }
+    else {
+        Err(error::Error::InvalidOptions(format!("The page size must be between 1 and {}, but was {}.", MAX_PAGE_SIZE, value)))
+    }
+}
+
+/// Retrieves the page at the given URL, which is typically one of the `prev` or `next` links of another page. The number of the page is recorded when tracing, if it is known.
+pub(crate) async fn follow_link<P : DeserializeOwned>(client : &Client, url : &str, page : Option<usize>) -> Result<P, error::Error> {
+    let url = reqwest::Url::parse(url).map_err(error::Error::UrlParse)?;
+    let res = client.send_page(reqwest::Method::GET, url, None, page).await?;
+
+    match res.status {
+        reqwest::StatusCode::OK => {
+            let response : P = error::from_json(&res.body)?;
+
+            Ok(response)
+        },
+        _ => Err(error::Error::from_response(res)),
+    }
+}
+
+struct StreamState<I> {
+    next : Option<Result<String, error::Error>>,
+    buffer : VecDeque<I>,
+    pages : usize,
+    items : usize,
+}
+
+/// Creates a stream of the individual resources across every page of a list, starting at the given URL. Pages are only requested once the resources of the previous page have been consumed, and the stream ends after the first error.
+pub(crate) fn stream<'a, P>(client : &'a Client, url : Result<reqwest::Url, error::Error>, limits : StreamLimits) -> impl Stream<Item = Result<P::Item, error::Error>> + 'a
+where
+    P : Paginated + 'a,
+{
+    let state = StreamState {
+        next : Some(url.map(String::from)),
+        buffer : VecDeque::new(),
+        pages : 0,
+        items : 0,
+    };
+
+    futures::stream::unfold(state, move |mut state| async move {
+        loop {
+            if limits.max_items.is_some_and(|max| state.items >= max) {
+                return None;
+            }
+
+            if let Some(item) = state.buffer.pop_front() {
+                state.items += 1;
+                return Some((Ok(item), state));
+            }
+
+            if limits.max_pages.is_some_and(|max| state.pages >= max) {
+                return None;
+            }
+
+            let url = match state.next.take()? {
+                Ok(url) => url,
+                Err(err) => return Some((Err(err), state)),
+            };
+
+            match follow_link::<P>(client, &url, Some(state.pages + 1)).await {
+                Ok(page) => {
+                    state.pages += 1;
+                    state.next = page.links().next.clone().map(Ok);
+                    state.buffer = page.into_data().into();
+                },
+                Err(err) => return Some((Err(err), state)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::memory::{InMemoryApi, Store};
+    use crate::v1::transactions;
+
+    use futures::TryStreamExt;
+
+    async fn collect(limits : StreamLimits) -> Vec<String> {
+        let api = InMemoryApi::new(Store::sample());
+
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.page_size(2);
+
+        api.client()
+            .list_transactions_stream(&options, limits)
+            .map_ok(|transaction| transaction.id.into_inner())
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn streams_stop_at_their_limits() {
+        assert_eq!(collect(StreamLimits::default()).await.len(), 5);
+        assert_eq!(collect(StreamLimits::default().max_items(3)).await, vec!["transaction-5", "transaction-4", "transaction-3"]);
+        assert_eq!(collect(StreamLimits::default().max_pages(2)).await.len(), 4);
+        assert_eq!(collect(StreamLimits::default().max_pages(2).max_items(1)).await.len(), 1);
+        assert!(collect(StreamLimits::default().max_items(0)).await.is_empty());
+        assert!(collect(StreamLimits::default().max_pages(0)).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pages_can_be_followed_without_the_trait() {
+        let api = InMemoryApi::new(Store::sample());
+
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.page_size(3);
+
+        let first = api.client().list_transactions(&options).await.unwrap();
+        let second = transactions::ListTransactionsResponse::next(&first, api.client()).await.unwrap().unwrap();
+        let back = transactions::ListTransactionsResponse::prev(&second, api.client()).await.unwrap().unwrap();
+
+        assert_eq!(second.data.len(), 2);
+        assert_eq!(back.data[0].id, first.data[0].id);
+        assert!(transactions::ListTransactionsResponse::next(&second, api.client()).await.is_none());
+    }
+
+    #[test]
+    fn page_sizes_are_validated() {
+        assert!(validate_page_size(1).is_ok());
+        assert!(validate_page_size(MAX_PAGE_SIZE).is_ok());
+        assert!(matches!(validate_page_size(0), Err(error::Error::InvalidOptions(_))));
+        assert!(matches!(validate_page_size(MAX_PAGE_SIZE + 1), Err(error::Error::InvalidOptions(_))));
+    }
+}
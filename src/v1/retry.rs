@@ -0,0 +1,204 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+/// Configuration for how a `Client` retries requests which fail due to rate limiting (`429`), server errors (`5XX`) or transient connection problems.
+///
+/// By default `GET` requests are retried up to 3 attempts in total, with an exponential backoff starting at 500 milliseconds. Requests which modify data (`POST`, `PATCH` and `DELETE`) are only retried once `retry_non_idempotent` has been enabled.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The total number of attempts made for a request, including the first.
+    max_attempts : u32,
+    /// The delay before the first retry, which is doubled for each subsequent retry.
+    initial_backoff : Duration,
+    /// The upper bound on the delay between any two attempts.
+    max_backoff : Duration,
+    /// Whether to randomise each delay to avoid many clients retrying in lockstep.
+    jitter : bool,
+    /// Whether requests which modify data should also be retried.
+    retry_non_idempotent : bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts : 3,
+            initial_backoff : Duration::from_millis(500),
+            max_backoff : Duration::from_secs(30),
+            jitter : true,
+            retry_non_idempotent : false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy which never retries, making exactly one attempt for every request.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts : 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the total number of attempts made for a request, including the first. Values less than 1 are treated as 1.
+    pub fn max_attempts(mut self, value : u32) -> Self {
+        self.max_attempts = value.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry. Each subsequent retry doubles the delay.
+    pub fn initial_backoff(mut self, value : Duration) -> Self {
+        self.initial_backoff = value;
+        self
+    }
+
+    /// Sets the upper bound on the delay between attempts. If the API responds with a `Retry-After` header asking for a longer delay than this, the request is not retried.
+    pub fn max_backoff(mut self, value : Duration) -> Self {
+        self.max_backoff = value;
+        self
+    }
+
+    /// Sets whether each delay is randomised (to between half and all of the computed backoff).
+    pub fn jitter(mut self, value : bool) -> Self {
+        self.jitter = value;
+        self
+    }
+
+    /// Sets whether `POST`, `PATCH` and `DELETE` requests (such as `categorise_transaction`, `add_tags` and `delete_tags`) are retried as well as `GET` requests.
+    pub fn retry_non_idempotent(mut self, value : bool) -> Self {
+        self.retry_non_idempotent = value;
+        self
+    }
+
+    /// Whether a request with the given method may be retried at all under this policy.
+    pub(crate) fn applies_to(&self, method : &reqwest::Method) -> bool {
+        *method == reqwest::Method::GET || self.retry_non_idempotent
+    }
+
+    /// Whether another attempt is allowed after `attempt` attempts have been made.
+    pub(crate) fn allows_attempt(&self, attempt : u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Computes how long to wait after the given (1-based) attempt failed. Returns `None` if the server asked for a longer delay than `max_backoff`.
+    pub(crate) fn delay(&self, attempt : u32, retry_after : Option<Duration>) -> Option<Duration> {
+        if let Some(value) = retry_after {
+            return if value <= self.max_backoff { Some(value) } else { None };
+        }
+
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self.initial_backoff.saturating_mul(factor).min(self.max_backoff);
+
+        if self.jitter {
+            let half = backoff / 2;
+            let nanos = half.as_nanos() as u64;
+            let offset = if nanos == 0 { 0 } else { random() % nanos };
+            Some(half + Duration::from_nanos(offset))
+        }
+        else {
+            Some(backoff)
+        }
+    }
+}
+
+/// Whether a response with the given status code should be retried.
+pub(crate) fn is_retryable_status(status : reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Whether a failed request should be retried, being the case only for errors which occur before a response is received.
+pub(crate) fn is_retryable_error(error : &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
+/// Parses a `Retry-After` header, which may be given either as a number of seconds or as a HTTP date.
+pub(crate) fn retry_after(headers : &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+/// A cheap source of randomness for jitter, which need not be of cryptographic quality.
+fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos() as u64);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy::default().jitter(false).initial_backoff(Duration::from_millis(500)).max_backoff(Duration::from_secs(3));
+
+        assert_eq!(policy.delay(1, None), Some(Duration::from_millis(500)));
+        assert_eq!(policy.delay(2, None), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(3, None), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(4, None), Some(Duration::from_secs(3)));
+        assert_eq!(policy.delay(40, None), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn jitter_stays_between_half_and_all_of_the_backoff() {
+        let policy = RetryPolicy::default().initial_backoff(Duration::from_secs(1));
+
+        for attempt in 1..=4 {
+            let backoff = Duration::from_secs(1 << (attempt - 1));
+
+            for _ in 0..50 {
+                let delay = policy.delay(attempt, None).unwrap();
+                assert!(delay >= backoff / 2 && delay <= backoff, "{:?} for attempt {}", delay, attempt);
+            }
+        }
+
+        assert_eq!(RetryPolicy::default().initial_backoff(Duration::ZERO).delay(1, None), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_is_followed_unless_too_long() {
+        let policy = RetryPolicy::default().max_backoff(Duration::from_secs(10));
+
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(10))), Some(Duration::from_secs(10)));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(11))), None);
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, reqwest::header::HeaderValue::from_static(" 120 "));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        headers.insert(reqwest::header::RETRY_AFTER, reqwest::header::HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(reqwest::header::RETRY_AFTER, reqwest::header::HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn only_idempotent_requests_are_retried_by_default() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.applies_to(&reqwest::Method::GET));
+
+        for method in [reqwest::Method::POST, reqwest::Method::PATCH, reqwest::Method::DELETE] {
+            assert!(!policy.applies_to(&method));
+            assert!(policy.clone().retry_non_idempotent(true).applies_to(&method));
+        }
+
+        assert!(policy.allows_attempt(2));
+        assert!(!policy.allows_attempt(3));
+        assert!(!RetryPolicy::none().allows_attempt(1));
+    }
+
+    #[test]
+    fn statuses_are_classified() {
+        assert!(is_retryable_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(reqwest::StatusCode::NOT_FOUND));
+    }
+}
@@ -0,0 +1,51 @@
+/// The value which replaces anything scrubbed from a cassette or trace.
+pub const REDACTED : &str = "[REDACTED]";
+
+/// The JSON fields scrubbed from request and response bodies by default, being those which may identify the owner of the account or allow someone to act on their behalf.
+pub const DEFAULT_SCRUBBED_FIELDS : &[&str] = &[
+    "displayName",
+    "description",
+    "message",
+    "rawText",
+    "cardNumberSuffix",
+    "secretKey",
+    "url",
+];
+
+/// Replaces the access token wherever it appears in the text and, if the text is a JSON document, every string held by one of the given fields.
+pub(crate) fn scrub_text<F : AsRef<str>>(text : &str, token : Option<&str>, fields : &[F]) -> String {
+    let text = match token {
+        Some(token) if !token.is_empty() => text.replace(token, REDACTED),
+        _ => String::from(text),
+    };
+
+    match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(mut value) if value.is_object() || value.is_array() => {
+            scrub_value(&mut value, fields);
+            value.to_string()
+        },
+        _ => text,
+    }
+}
+
+/// Replaces every string held by one of the given fields, at any depth.
+pub(crate) fn scrub_value<F : AsRef<str>>(value : &mut serde_json::Value, fields : &[F]) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if value.is_string() && fields.iter().any(|f| f.as_ref() == key) {
+                    *value = serde_json::Value::String(String::from(REDACTED));
+                }
+                else {
+                    scrub_value(value, fields);
+                }
+            }
+        },
+        serde_json::Value::Array(values) => {
+            for value in values {
+                scrub_value(value, fields);
+            }
+        },
+        _ => {},
+    }
+}
@@ -1,12 +1,328 @@
-use serde::Deserialize;
+use crate::v1::error;
 
-#[derive(Deserialize, Debug)]
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! resource_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates the identifier from its string representation.
+            pub fn new(value : impl Into<String>) -> Self {
+                Self(value.into())
+            }
+
+            /// The string representation of the identifier.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Whether the identifier is empty, which is never the case for identifiers returned by the API.
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            /// Consumes the identifier, returning its string representation.
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value : String) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value : &str) -> Self {
+                Self(String::from(value))
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+    }
+}
+
+resource_id!(
+    /// The unique identifier of an account.
+    AccountId
+);
+
+resource_id!(
+    /// The unique identifier of a transaction.
+    TransactionId
+);
+
+resource_id!(
+    /// The unique identifier of a category. This is a human-readable but URL-safe value, such as `restaurants-and-cafes`.
+    CategoryId
+);
+
+resource_id!(
+    /// The label of a tag, which also acts as the tag’s unique identifier.
+    TagId
+);
+
+resource_id!(
+    /// The unique identifier of a webhook.
+    WebhookId
+);
+
+/// A date-time formatted according to rfc-3339, as returned by the API. Date-times are compared (and ordered) by the instant they represent, regardless of their offset from UTC, and keep the string they were parsed from for display.
+#[derive(Clone, Debug)]
+pub struct DateTime {
+    value : String,
+    seconds : i64,
+    nanos : u32,
+    offset : i32,
+}
+
+impl DateTime {
+    /// The string representation of the date-time, as returned by the API.
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// The number of whole seconds since the Unix epoch.
+    pub fn timestamp(&self) -> i64 {
+        self.seconds
+    }
+
+    /// The number of nanoseconds past the whole second given by `timestamp`.
+    pub fn timestamp_subsec_nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    /// The offset from UTC (in seconds) the date-time was given in.
+    pub fn offset_seconds(&self) -> i32 {
+        self.offset
+    }
+
+    /// Converts the date-time into a `chrono::DateTime`, keeping the offset it was given in.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        let offset = chrono::FixedOffset::east_opt(self.offset).expect("Offsets are validated to be less than a day.");
+        chrono::DateTime::from_timestamp(self.seconds, self.nanos).expect("Years are validated to have four digits.").with_timezone(&offset)
+    }
+}
+
+impl std::str::FromStr for DateTime {
+    type Err = error::Error;
+
+    fn from_str(value : &str) -> Result<Self, Self::Err> {
+        let (seconds, nanos, offset) = parse_rfc3339(value).ok_or_else(|| invalid_date_time("date-time", value))?;
+
+        Ok(DateTime {
+            value : String::from(value),
+            seconds,
+            nanos,
+            offset,
+        })
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other : &Self) -> bool {
+        (self.seconds, self.nanos) == (other.seconds, other.nanos)
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other : &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other : &Self) -> std::cmp::Ordering {
+        (self.seconds, self.nanos).cmp(&(other.seconds, other.nanos))
+    }
+}
+
+impl std::hash::Hash for DateTime {
+    fn hash<H : std::hash::Hasher>(&self, state : &mut H) {
+        (self.seconds, self.nanos).hash(state);
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl AsRef<str> for DateTime {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl From<DateTime> for String {
+    fn from(value : DateTime) -> Self {
+        value.value
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D : serde::Deserializer<'de>>(deserializer : D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for DateTime {
+    fn serialize<S : serde::Serializer>(&self, serializer : S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value)
+    }
+}
+
+/// Formats a `chrono::DateTime` according to rfc-3339, for use in query parameters.
+#[cfg(feature = "chrono")]
+pub(crate) fn from_chrono<Tz : chrono::TimeZone>(value : &chrono::DateTime<Tz>) -> String where Tz::Offset : fmt::Display {
+    value.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+}
+
+fn invalid_date_time(name : &str, value : &str) -> error::Error {
+    error::Error::InvalidOptions(format!("The {} value ({:?}) is not a rfc-3339 date-time.", name, value))
+}
+
+/// Converts the date-time given for the named option into seconds and nanoseconds since the Unix epoch, so that it can be compared with others. This fails if the value is not formatted according to rfc-3339.
+pub(crate) fn parse_date_time(name : &str, value : &str) -> Result<(i64, u32), error::Error> {
+    parse_rfc3339(value).map(|(seconds, nanos, _)| (seconds, nanos)).ok_or_else(|| invalid_date_time(name, value))
+}
+
+/// Parses a rfc-3339 date-time into seconds and nanoseconds since the Unix epoch, along with its offset from UTC in seconds.
+fn parse_rfc3339(value : &str) -> Option<(i64, u32, i32)> {
+    let digits = |from : usize, to : usize| -> Option<i64> {
+        let part = value.get(from..to)?;
+        if part.bytes().all(|b| b.is_ascii_digit()) { part.parse().ok() } else { None }
+    };
+
+    let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+    let bytes = value.as_bytes();
+
+    if bytes.len() < 20
+        || separators.iter().any(|(i, c)| bytes[*i] != *c)
+        || !matches!(bytes[10], b'T' | b't' | b' ') {
+        return None;
+    }
+
+    let (year, month, day) = (digits(0, 4)?, digits(5, 7)?, digits(8, 10)?);
+    let (hour, minute, second) = (digits(11, 13)?, digits(14, 16)?, digits(17, 19)?);
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+
+    if !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &value[19..];
+    let mut nanos = 0;
+
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let length = fraction.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if length == 0 || length > 9 {
+            return None;
+        }
+        nanos = format!("{:0<9}", &fraction[..length]).parse().ok()?;
+        rest = &fraction[length..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let start = value.len() - 6;
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (hours, minutes) = (digits(start + 1, start + 3)?, digits(start + 4, start + 6)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 3600 + minutes * 60)
+        },
+        _ => return None,
+    };
+
+    // Days since the Unix epoch of the civil date, from Howard Hinnant's `days_from_civil`.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    Some((days * 86400 + hour * 3600 + minute * 60 + second - offset, nanos, offset as i32))
+}
+
+/// Formats a number of seconds since the Unix epoch as a rfc-3339 date-time with the given offset from UTC (in seconds), as the API does.
+#[cfg(any(test, feature = "testing"))]
+pub(crate) fn format_timestamp(seconds : i64, offset : i32) -> String {
+    let local = seconds + offset as i64;
+    let (days, time) = (local.div_euclid(86400), local.rem_euclid(86400));
+
+    // The civil date of a number of days since the Unix epoch, from Howard Hinnant's `civil_from_days`.
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+        year, month, day, time / 3600, time % 3600 / 60, time % 60, sign, offset / 3600, offset % 3600 / 60
+    )
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AccountTypeEnum {
     Saver,
     Transactional,
 }
 
+impl fmt::Display for AccountTypeEnum {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Saver => write!(f, "SAVER"),
+            Self::Transactional => write!(f, "TRANSACTIONAL"),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MoneyObject {
@@ -18,20 +334,38 @@ pub struct MoneyObject {
     pub value_in_base_units : i64,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OwnershipTypeEnum {
     Individual,
     Joint,
 }
 
-#[derive(Deserialize, Debug)]
+impl fmt::Display for OwnershipTypeEnum {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Individual => write!(f, "INDIVIDUAL"),
+            Self::Joint => write!(f, "JOINT"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionStatusEnum {
     Held,
     Settled,
 }
 
+impl fmt::Display for TransactionStatusEnum {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Held => write!(f, "HELD"),
+            Self::Settled => write!(f, "SETTLED"),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct HoldInfoObject {
@@ -89,6 +423,9 @@ pub enum WebhookEventTypeEnum {
     TransactionSettled,
     TransactionDeleted,
     Ping,
+    /// An event type added to the API since this version of the crate, which can be acknowledged but not interpreted.
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Debug)]
@@ -98,3 +435,104 @@ pub enum WebhookDeliveryStatusEnum {
     Undeliverable,
     BadResponseCode,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_times_are_parsed() {
+        let cases = [
+            ("1970-01-01T00:00:00Z", (0, 0)),
+            ("1970-01-01t00:00:00z", (0, 0)),
+            ("1970-01-01 00:00:00+00:00", (0, 0)),
+            ("1969-12-31T23:59:59Z", (-1, 0)),
+            ("2000-03-01T00:00:00Z", (951868800, 0)),
+            ("2020-02-29T12:00:00Z", (1582977600, 0)),
+            ("2000-02-29T00:00:00Z", (951782400, 0)),
+            ("2021-04-30T00:00:00Z", (1619740800, 0)),
+            ("2020-01-01T01:02:03.25+10:00", (1577804523, 250000000)),
+            ("2020-01-01T01:02:03.000000001Z", (1577840523, 1)),
+            ("2019-12-31T23:59:59-00:30", (1577838599, 0)),
+            ("2020-01-01T00:00:00+23:59", (1577750460, 0)),
+            ("2016-12-31T23:59:60Z", (1483228800, 0)),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(parse_date_time("value", value).unwrap(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn date_times_are_ordered_by_instant() {
+        let parse = |value : &str| serde_json::from_str::<DateTime>(&format!("{:?}", value)).unwrap();
+
+        let mut values = [parse("2020-01-01T09:00:00+10:00"), parse("2019-12-31T22:00:00Z"), parse("2019-12-31T23:00:00.5Z")];
+        values.sort();
+
+        let sorted : Vec<&str> = values.iter().map(|v| v.as_str()).collect();
+        assert_eq!(sorted, vec!["2019-12-31T22:00:00Z", "2020-01-01T09:00:00+10:00", "2019-12-31T23:00:00.5Z"]);
+
+        assert_eq!(parse("2020-01-01T10:00:00+10:00"), parse("2020-01-01T00:00:00Z"));
+        assert_eq!(parse("2020-01-01T10:00:00+10:00").offset_seconds(), 36000);
+        assert_eq!(serde_json::to_string(&parse("2020-01-01T10:00:00+10:00")).unwrap(), r#""2020-01-01T10:00:00+10:00""#);
+        assert!(serde_json::from_str::<DateTime>(r#""2020-01-01""#).is_err());
+    }
+
+    #[test]
+    fn timestamps_are_formatted() {
+        let cases = [
+            ((0, 0), "1970-01-01T00:00:00+00:00"),
+            ((-1, 0), "1969-12-31T23:59:59+00:00"),
+            ((951782400, 0), "2000-02-29T00:00:00+00:00"),
+            ((1582977600, 0), "2020-02-29T12:00:00+00:00"),
+            ((1551398400, 0), "2019-03-01T00:00:00+00:00"),
+            ((1577836799, 0), "2019-12-31T23:59:59+00:00"),
+            ((1577836800, 0), "2020-01-01T00:00:00+00:00"),
+            ((1577800800, 36000), "2020-01-01T00:00:00+10:00"),
+            ((1577836800, 36000), "2020-01-01T10:00:00+10:00"),
+            ((1577836800, -19800), "2019-12-31T18:30:00-05:30"),
+            ((0, -1800), "1969-12-31T23:30:00-00:30"),
+        ];
+
+        for ((seconds, offset), expected) in cases {
+            assert_eq!(format_timestamp(seconds, offset), expected);
+            assert_eq!(parse_date_time("value", expected).unwrap(), (seconds, 0));
+        }
+    }
+
+    #[test]
+    fn malformed_date_times_are_rejected() {
+        let cases = [
+            "",
+            "2020-01-01",
+            "2020-01-01T00:00:00",
+            "2020-01-01X00:00:00Z",
+            "2020/01/01T00:00:00Z",
+            "2020-00-01T00:00:00Z",
+            "2020-13-01T00:00:00Z",
+            "2020-01-32T00:00:00Z",
+            "2020-02-30T00:00:00Z",
+            "2021-02-29T00:00:00Z",
+            "1900-02-29T00:00:00Z",
+            "2021-04-31T00:00:00Z",
+            "2021-11-31T00:00:00Z",
+            "2020-01-01T24:00:00Z",
+            "2020-01-01T00:60:00Z",
+            "2020-01-01T00:00:61Z",
+            "2020-01-01T00:00:00.Z",
+            "2020-01-01T00:00:00.0000000001Z",
+            "2020-01-01T00:00:00+1000",
+            "2020-01-01T00:00:00*10:00",
+            "2020-01-01T00:00:00+24:00",
+            "2020-01-01T00:00:00+10:60",
+            "2020-01-01T00:00:00+99:99",
+            "2020-01-01T00:00:00-1a:00",
+            "+020-01-01T00:00:00Z",
+        ];
+
+        for value in cases {
+            assert!(matches!(parse_date_time("value", value), Err(error::Error::InvalidOptions(_))), "{}", value);
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use crate::v1::{Client, error, BASE_URL};
+use crate::v1::{Client, error, pagination, standard, validate_id};
 
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 
 // ----------------- Response Objects -----------------
@@ -8,7 +9,7 @@ use serde::{Deserialize, Serialize};
 pub struct ListTagsResponse {
     /// The list of tags returned in this response.
     pub data : Vec<TagResource>,
-    pub links : ResponseLinks,
+    pub links : pagination::PageLinks,
 }
 
 #[derive(Deserialize, Debug)]
@@ -16,7 +17,7 @@ pub struct TagResource {
     /// The type of this resource: `tags`
     pub r#type : String,
     /// The label of the tag, which also acts as the tag’s unique identifier.
-    pub id : String,
+    pub id : standard::TagId,
     pub relationships : Relationships,
 }
 
@@ -36,12 +37,6 @@ pub struct TransactionLinks {
     pub related : String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ResponseLinks {
-    pub prev : Option<String>,
-    pub next : Option<String>,
-}
-
 // ----------------- Input Objects -----------------
 
 #[derive(Default)]
@@ -51,35 +46,45 @@ pub struct ListTagsOptions {
 }
 
 impl ListTagsOptions {
-    /// Sets the page size.
-    pub fn page_size(&mut self, value : u8) {
+    /// Sets the page size, which must be between 1 and 100.
+    pub fn page_size(&mut self, value : u8) -> &mut Self {
         self.page_size = Some(value);
+        self
+    }
+
+    /// Checks that the options are valid, as is done automatically before any request is made with them.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if let Some(value) = self.page_size {
+            pagination::validate_page_size(value)?;
+        }
+
+        Ok(())
     }
 
     fn add_params(&self, url : &mut reqwest::Url) {
-        let mut query = String::new();
+        let mut query = Vec::new();
 
         if let Some(value) = &self.page_size {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("page[size]={}", value));
+            query.push(("page[size]", value.to_string()));
         }
 
         if !query.is_empty() {
-            url.set_query(Some(&query))
+            url.query_pairs_mut().extend_pairs(query);
         }
     }
 }
 
 // ----------------- Request Objects -----------------
 
+/// The maximum number of tags which may be present on a single transaction.
+pub const MAX_TAGS_PER_TRANSACTION : usize = 6;
+
 #[derive(Serialize)]
 struct TagInputResourceIdentifier {
     /// The type of this resource: `tags`
     r#type : String,
     /// The label of the tag, which also acts as the tag’s unique identifier.
-    id : String,
+    id : standard::TagId,
 }
 
 #[derive(Serialize)]
@@ -88,38 +93,58 @@ struct TagRequest {
     data : Vec<TagInputResourceIdentifier>
 }
 
+/// Checks that a list of tags to add or remove could be valid for a single transaction.
+fn validate_tags(tags : &[standard::TagId]) -> Result<(), error::Error> {
+    if tags.len() > MAX_TAGS_PER_TRANSACTION {
+        return Err(error::Error::InvalidArgument(format!("No more than {} tags may be present on a transaction, but {} were provided.", MAX_TAGS_PER_TRANSACTION, tags.len())));
+    }
+
+    if tags.iter().any(|t| t.is_empty()) {
+        return Err(error::Error::InvalidArgument(String::from("The provided tags must not be empty.")));
+    }
+
+    Ok(())
+}
+
 impl Client {
     /// Retrieve a list of all tags currently in use. The returned list is paginated and can be scrolled by following the `next` and `prev`  links where present. Results are ordered lexicographically. The transactions relationship for each tag exposes a link to get the transactions with the given tag.
     pub async fn list_tags(&self, options : &ListTagsOptions) -> Result<ListTagsResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/tags", BASE_URL)).map_err(error::Error::UrlParse)?;
+        options.validate()?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/tags", self.base_url)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let tags_response : ListTagsResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let tags_response : ListTagsResponse = error::from_json(&res.body)?;
 
                 Ok(tags_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
+    /// Retrieve every tag currently in use as a stream, following the `next` link of each page as it is needed. Results are ordered lexicographically. The `limits` can be used to cap the total number of tags or pages retrieved.
+    pub fn list_tags_stream<'a>(&'a self, options : &ListTagsOptions, limits : pagination::StreamLimits) -> impl Stream<Item = Result<TagResource, error::Error>> + 'a {
+        let url = options
+            .validate()
+            .and_then(|_| reqwest::Url::parse(&format!("{}/tags", self.base_url)).map_err(error::Error::UrlParse))
+            .map(|mut url| {
+                options.add_params(&mut url);
+                url
+            });
+
+        pagination::stream::<ListTagsResponse>(self, url, limits)
+    }
+
     /// Associates one or more tags with a specific transaction. No more than 6 tags may be present on any single transaction. Duplicate tags are silently ignored. The associated tags, along with this request URL, are also exposed via the tags relationship on the transaction resource returned from `get_transaction`.
-    pub async fn add_tags(&self, transaction_id : &str, tags : Vec<String>) -> Result<(), error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/transactions/{}/relationships/tags", BASE_URL, transaction_id)).map_err(error::Error::UrlParse)?;
+    pub async fn add_tags(&self, transaction_id : &standard::TransactionId, tags : Vec<standard::TagId>) -> Result<(), error::Error> {
+        validate_id("transaction ID", transaction_id.as_str())?;
+        validate_tags(&tags)?;
+
+        let url = reqwest::Url::parse(&format!("{}/transactions/{}/relationships/tags", self.base_url, transaction_id)).map_err(error::Error::UrlParse)?;
 
         let tags =
             tags
@@ -133,31 +158,22 @@ impl Client {
         let body = TagRequest { data : tags };
         let body = serde_json::to_string(&body).map_err(error::Error::Serialize)?;
 
-        let res = reqwest::Client::new()
-            .post(url)
-            .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::POST, url, Some(body)).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::NO_CONTENT => {
                 Ok(())
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Disassociates one or more tags from a specific transaction. Tags that are not associated are silently ignored. The associated tags, along with this request URL, are also exposed via the tags relationship on the transaction resource returned from `get_transaction`.
-    pub async fn delete_tags(&self, transaction_id : &str, tags : Vec<String>) -> Result<(), error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/transactions/{}/relationships/tags", BASE_URL, transaction_id)).map_err(error::Error::UrlParse)?;
+    pub async fn delete_tags(&self, transaction_id : &standard::TransactionId, tags : Vec<standard::TagId>) -> Result<(), error::Error> {
+        validate_id("transaction ID", transaction_id.as_str())?;
+        validate_tags(&tags)?;
+
+        let url = reqwest::Url::parse(&format!("{}/transactions/{}/relationships/tags", self.base_url, transaction_id)).map_err(error::Error::UrlParse)?;
 
         let tags =
             tags
@@ -171,29 +187,17 @@ impl Client {
         let body = TagRequest { data : tags };
         let body = serde_json::to_string(&body).map_err(error::Error::Serialize)?;
 
-        let res = reqwest::Client::new()
-            .delete(url)
-            .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::DELETE, url, Some(body)).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::NO_CONTENT => {
                 Ok(())
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 }
 
 // ----------------- Page Navigation -----------------
 
-implement_pagination_v1!(ListTagsResponse);
+pagination::implement_paginated!(ListTagsResponse, TagResource);
@@ -0,0 +1,252 @@
+use crate::v1::{error, transport};
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use futures::channel::oneshot;
+
+pub use crate::v1::memory::{Store, account, category, transaction};
+
+// ----------------- Server -----------------
+
+/// A local HTTP server which implements the endpoints of the Up API over the data in a `Store`, for developing and testing against without a real account.
+///
+/// Any bearer token is accepted. The server stops when it is dropped.
+///
+/// ```
+/// use up_api::v1::Client;
+/// use up_api::v1::accounts::ListAccountsOptions;
+/// use up_api::v1::test_server::{Store, TestServer};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let server = TestServer::start(Store::sample()).await.unwrap();
+/// let client = Client::builder(String::from("up:yeah:token")).base_url(&server.base_url()).build().unwrap();
+///
+/// let accounts = client.list_accounts(&ListAccountsOptions::default()).await.unwrap();
+/// assert_eq!(accounts.data.len(), 2);
+/// # }
+/// ```
+pub struct TestServer {
+    address : SocketAddr,
+    store : Store,
+    shutdown : Option<oneshot::Sender<()>>,
+}
+
+impl TestServer {
+    /// Starts the server on a free local port, serving (and modifying) the given store. This must be called within a Tokio runtime.
+    pub async fn start(store : Store) -> Result<Self, error::Error> {
+        let start_error = |err : &dyn std::fmt::Display| error::Error::Transport(format!("Failed to start the test server: {}", err));
+
+        let listener = std::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).map_err(|err| start_error(&err))?;
+        listener.set_nonblocking(true).map_err(|err| start_error(&err))?;
+        let address = listener.local_addr().map_err(|err| start_error(&err))?;
+
+        let base = format!("http://{}/api/v1", address);
+        let shared = store.clone();
+
+        let make_service = hyper::service::make_service_fn(move |_| {
+            let store = shared.clone();
+            let base = base.clone();
+
+            async move {
+                Ok::<_, Infallible>(hyper::service::service_fn(move |req| {
+                    let store = store.clone();
+                    let base = base.clone();
+
+                    async move {
+                        Ok::<_, Infallible>(handle(&store, &base, req).await)
+                    }
+                }))
+            }
+        });
+
+        let (sender, receiver) = oneshot::channel::<()>();
+
+        let server = hyper::Server::from_tcp(listener).map_err(|err| start_error(&err))?.serve(make_service);
+
+        tokio::spawn(server.with_graceful_shutdown(async {
+            receiver.await.ok();
+        }));
+
+        Ok(TestServer {
+            address,
+            store,
+            shutdown : Some(sender),
+        })
+    }
+
+    /// The base URL of the server, to be passed to `ClientBuilder::base_url`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/api/v1", self.address)
+    }
+
+    /// The store served by the server.
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if let Some(sender) = self.shutdown.take() {
+            sender.send(()).ok();
+        }
+    }
+}
+
+async fn handle(store : &Store, base : &str, req : hyper::Request<hyper::Body>) -> hyper::Response<hyper::Body> {
+    let (parts, body) = req.into_parts();
+
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(bytes) if !bytes.is_empty() => Some(String::from_utf8_lossy(&bytes).into_owned()),
+        _ => None,
+    };
+
+    let response = match reqwest::Url::parse(&format!("{}{}", base.trim_end_matches("/api/v1"), parts.uri)) {
+        Ok(url) => store.respond(&transport::Request {
+            method : parts.method,
+            url,
+            headers : parts.headers,
+            body,
+        }),
+        Err(_) => transport::Response::empty(reqwest::StatusCode::BAD_REQUEST),
+    };
+
+    let mut builder = hyper::Response::builder().status(response.status);
+
+    if let Some(headers) = builder.headers_mut() {
+        headers.extend(response.headers);
+    }
+
+    builder.body(hyper::Body::from(response.body)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::{Client, accounts, categories, pagination, standard, transactions, webhooks};
+
+    use futures::TryStreamExt;
+
+    async fn start() -> (TestServer, Client) {
+        let server = TestServer::start(Store::sample()).await.unwrap();
+        let client = Client::builder(String::from("up:yeah:token")).base_url(&server.base_url()).build().unwrap();
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn pages_are_linked() {
+        let (_server, client) = start().await;
+
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.page_size(2);
+
+        let ids : Vec<String> =
+            client
+            .list_transactions_stream(&options, pagination::StreamLimits::default())
+            .map_ok(|t| t.id.into_inner())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(ids, vec!["transaction-5", "transaction-4", "transaction-3", "transaction-2", "transaction-1"]);
+
+        let first = client.list_transactions(&options).await.unwrap();
+        let second = pagination::Paginated::next(&first, &client).await.unwrap().unwrap();
+        let back = pagination::Paginated::prev(&second, &client).await.unwrap().unwrap();
+
+        assert_eq!(second.data[0].id.as_str(), "transaction-3");
+        assert_eq!(back.data[0].id.as_str(), "transaction-5");
+        assert!(back.links.prev.is_none());
+    }
+
+    #[tokio::test]
+    async fn filters_are_applied() {
+        let (_server, client) = start().await;
+
+        let mut options = accounts::ListAccountsOptions::default();
+        options.filter_account_type(standard::AccountTypeEnum::Saver);
+        assert_eq!(client.list_accounts(&options).await.unwrap().data[0].id.as_str(), "savings");
+
+        let mut options = transactions::ListTransactionsOptions::default();
+        options.filter_status(standard::TransactionStatusEnum::Settled).filter_category(standard::CategoryId::from("good-life"));
+        let response = client.list_transactions_by_account(&standard::AccountId::from("spending"), &options).await.unwrap();
+        assert_eq!(response.data.len(), 1);
+        assert_eq!(response.data[0].attributes.description, "Bills Cafe");
+
+        let mut options = categories::ListCategoriesOptions::default();
+        options.filter_parent(standard::CategoryId::from("home"));
+        assert_eq!(client.list_categories(&options).await.unwrap().data.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn relationships_can_be_updated() {
+        let (server, client) = start().await;
+        let id = standard::TransactionId::from("transaction-1");
+
+        client.categorise_transaction(&id, Some(&standard::CategoryId::from("takeaway"))).await.unwrap();
+        client.add_tags(&id, vec![standard::TagId::from("Dinner"), standard::TagId::from("Friends")]).await.unwrap();
+        client.delete_tags(&id, vec![standard::TagId::from("Friends")]).await.unwrap();
+
+        let transaction = client.get_transaction(&id).await.unwrap().data;
+        assert_eq!(transaction.relationships.parent_category.data.unwrap().id.as_str(), "good-life");
+        assert_eq!(transaction.relationships.tags.data[0].id.as_str(), "Dinner");
+
+        let tags = client.list_tags(&Default::default()).await.unwrap();
+        assert_eq!(tags.data.len(), 1);
+        assert_eq!(server.store().transactions()[4]["relationships"]["tags"]["data"][0]["id"], "Dinner");
+
+        let error = client.categorise_transaction(&standard::TransactionId::from("transaction-4"), None).await.unwrap_err();
+        assert_eq!(error.status(), Some(reqwest::StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn webhooks_are_logged() {
+        let (_server, client) = start().await;
+
+        let webhook = client.create_webhook("https://example.com/hook", None).await.unwrap().data;
+        assert!(webhook.attributes.secret_key.is_some());
+
+        let event = client.ping_webhook(&webhook.id).await.unwrap().data;
+        let logs = client.list_webhook_logs(&webhook.id, &webhooks::ListWebhookLogsOptions::default()).await.unwrap();
+        assert_eq!(logs.data[0].relationships.webhook_event.data.id, event.id);
+
+        let listed = client.list_webhooks(&webhooks::ListWebhooksOptions::default()).await.unwrap();
+        assert!(listed.data[0].attributes.secret_key.is_none());
+
+        client.delete_webhook(&webhook.id).await.unwrap();
+        assert!(client.get_webhook(&webhook.id).await.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn errors_are_json_api_documents() {
+        let (server, client) = start().await;
+
+        let error = client.get_account(&standard::AccountId::from("missing")).await.unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(error.api_errors()[0].title, "Not Found");
+
+        let response = reqwest::get(format!("{}/transactions?filter%5Bsince%5D=yesterday", server.base_url())).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let anonymous = Client::builder(String::new()).base_url(&server.base_url()).build().unwrap();
+        assert!(anonymous.ping().await.unwrap_err().is_unauthorized());
+
+        let response = reqwest::Client::new()
+            .get(format!("{}/transactions?page%5Bsize%5D=500", server.base_url()))
+            .bearer_auth("up:yeah:token")
+            .send()
+            .await
+            .unwrap();
+
+        let error = crate::v1::error::Error::from_response(crate::v1::transport::Response {
+            status : response.status(),
+            headers : response.headers().clone(),
+            body : response.text().await.unwrap(),
+        });
+
+        assert!(error.is_validation());
+        assert_eq!(error.api_errors()[0].source.as_ref().unwrap().location(), Some(error::SourceLocation::Parameter(error::QueryParameter::PageSize)));
+    }
+}
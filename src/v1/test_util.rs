@@ -0,0 +1,47 @@
+use crate::v1::{Client, ClientBuilder, retry};
+use crate::v1::transport::Transport;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+
+/// The access token of the clients used in tests.
+pub(crate) const TOKEN : &str = "up:yeah:token";
+
+/// The secret key of the webhook which signs the events used in tests.
+pub(crate) const SECRET : &str = "n8cEPk2sJzXb7QfKvHrT";
+
+/// Creates a builder for a client which sends its requests with the given transport, retrying them without any delay.
+pub(crate) fn client_builder(transport : Arc<dyn Transport>) -> ClientBuilder {
+    Client::builder(String::from(TOKEN))
+        .transport(transport)
+        .retry_policy(retry::RetryPolicy::default().initial_backoff(Duration::ZERO).jitter(false))
+}
+
+/// Creates a client which sends its requests with the given transport, retrying them without any delay.
+pub(crate) fn client(transport : Arc<dyn Transport>) -> Client {
+    client_builder(transport).build().unwrap()
+}
+
+/// The body of a webhook event of the given type, as Up would deliver it. Every event type other than `PING` should have a transaction.
+pub(crate) fn event(id : &str, event_type : &str, transaction : Option<&str>) -> String {
+    let transaction = match transaction {
+        Some(transaction) => format!(r#","transaction":{{"data":{{"type":"transactions","id":"{}"}},"links":{{"related":"https://api.up.com.au/api/v1/transactions/{}"}}}}"#, transaction, transaction),
+        None => String::new(),
+    };
+
+    format!(
+        r#"{{"data":{{"type":"webhook-events","id":"{}","attributes":{{"eventType":"{}","createdAt":"2024-01-01T09:30:00+10:00"}},"relationships":{{"webhook":{{"data":{{"type":"webhooks","id":"webhook-1"}},"links":{{"related":"https://api.up.com.au/api/v1/webhooks/webhook-1"}}}}{}}}}}}}"#,
+        id,
+        event_type,
+        transaction,
+    )
+}
+
+/// The signature of a body with `SECRET`, as sent in the `X-Up-Authenticity-Signature` header.
+pub(crate) fn sign(body : &str) -> String {
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -1,5 +1,6 @@
-use crate::v1::{Client, error, BASE_URL, standard};
+use crate::v1::{Client, error, pagination, standard, validate_id};
 
+use futures::Stream;
 use serde::Deserialize;
 
 // ----------------- Response Objects -----------------
@@ -8,7 +9,7 @@ use serde::Deserialize;
 pub struct ListTransactionsResponse {
     /// The list of transactions returned in this response.
     pub data : Vec<TransactionResource>,
-    pub links : ResponseLinks,
+    pub links : pagination::PageLinks,
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,7 +23,7 @@ pub struct TransactionResource {
     /// The type of this resource: `transactions`
     pub r#type : String,
     /// The unique identifier for this transaction.
-    pub id : String,
+    pub id : standard::TransactionId,
     pub attributes : Attributes,
     pub relationships : Relationships,
     pub links : Option<TransactionResourceLinks>,
@@ -57,7 +58,7 @@ pub struct AccountData {
     /// The type of this resource: `accounts`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::AccountId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -77,7 +78,7 @@ pub struct TransferAccountData {
     /// The type of this resource: `accounts`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::AccountId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -97,7 +98,7 @@ pub struct CategoryData {
     /// The type of this resource: `categories`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::CategoryId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -119,7 +120,7 @@ pub struct ParentCategoryData {
     /// The type of this resource: `categories`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::CategoryId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -139,7 +140,7 @@ pub struct TagsData {
     /// The type of this resource: `tags`
     pub r#type : String,
     /// The label of the tag, which also acts as the tag’s unique identifier.
-    pub id : String,
+    pub id : standard::TagId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -175,17 +176,9 @@ pub struct Attributes {
     /// Information about the card used for this transaction, if applicable.
     pub card_purchase_method : Option<standard::CardPurchaseMethodObject>,
     /// The date-time at which this transaction settled. This field will be `null` for transactions that are currently in the `HELD` status.
-    pub settled_at : Option<String>,
+    pub settled_at : Option<standard::DateTime>,
     /// The date-time at which this transaction was first encountered.
-    pub created_at : String,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct ResponseLinks {
-    /// The link to the previous page in the results. If this value is null there is no previous page.
-    pub prev : Option<String>,
-    /// The link to the next page in the results. If this value is null there is no next page.
-    pub next : Option<String>,
+    pub created_at : standard::DateTime,
 }
 
 // ----------------- Input Objects -----------------
@@ -195,95 +188,117 @@ pub struct ListTransactionsOptions {
     /// The number of records to return in each page. 
     page_size : Option<u8>,
     /// The transaction status for which to return records. This can be used to filter `HELD` transactions from those that are `SETTLED`.
-    filter_status : Option<String>,
+    filter_status : Option<standard::TransactionStatusEnum>,
     /// The start date-time from which to return records, formatted according to rfc-3339. Not to be used for pagination purposes.
     filter_since : Option<String>,
     /// The end date-time up to which to return records, formatted according to rfc-3339. Not to be used for pagination purposes.
     filter_until : Option<String>,
     /// The category identifier for which to filter transactions. Both parent and child categories can be filtered through this parameter.
-    filter_category : Option<String>,
+    filter_category : Option<standard::CategoryId>,
     /// A transaction tag to filter for which to return records. If the tag does not exist, zero records are returned and a success response is given.
-    filter_tag : Option<String>,
+    filter_tag : Option<standard::TagId>,
 }
 
 impl ListTransactionsOptions {
-    /// Sets the page size.
-    pub fn page_size(&mut self, value : u8) {
+    /// Sets the page size, which must be between 1 and 100.
+    pub fn page_size(&mut self, value : u8) -> &mut Self {
         self.page_size = Some(value);
+        self
     }
 
     /// Sets the status filter value.
-    pub fn filter_status(&mut self, value : String) {
+    pub fn filter_status(&mut self, value : standard::TransactionStatusEnum) -> &mut Self {
         self.filter_status = Some(value);
+        self
+    }
+
+    /// Sets the since filter value, which must be a rfc-3339 formatted string (or a `standard::DateTime`).
+    pub fn filter_since(&mut self, value : impl Into<String>) -> &mut Self {
+        self.filter_since = Some(value.into());
+        self
+    }
+
+    /// Sets the since filter value from a `chrono::DateTime` in any time zone.
+    #[cfg(feature = "chrono")]
+    pub fn filter_since_chrono<Tz : chrono::TimeZone>(&mut self, value : &chrono::DateTime<Tz>) -> &mut Self where Tz::Offset : std::fmt::Display {
+        self.filter_since(standard::from_chrono(value))
     }
 
-    /// Sets the since filter value.
-    pub fn filter_since(&mut self, value : String) {
-        self.filter_since = Some(value);
+    /// Sets the until filter value, which must be a rfc-3339 formatted string (or a `standard::DateTime`).
+    pub fn filter_until (&mut self, value : impl Into<String>) -> &mut Self {
+        self.filter_until = Some(value.into());
+        self
     }
 
-    /// Sets the until filter value.
-    pub fn filter_until (&mut self, value : String) {
-        self.filter_until = Some(value);
+    /// Sets the until filter value from a `chrono::DateTime` in any time zone.
+    #[cfg(feature = "chrono")]
+    pub fn filter_until_chrono<Tz : chrono::TimeZone>(&mut self, value : &chrono::DateTime<Tz>) -> &mut Self where Tz::Offset : std::fmt::Display {
+        self.filter_until(standard::from_chrono(value))
     }
 
     /// Sets the category filter value.
-    pub fn filter_category(&mut self, value : String) {
+    pub fn filter_category(&mut self, value : standard::CategoryId) -> &mut Self {
         self.filter_category = Some(value);
+        self
     }
 
     /// Sets the tag filter value.
-    pub fn filter_tag (&mut self, value : String) {
+    pub fn filter_tag (&mut self, value : standard::TagId) -> &mut Self {
         self.filter_tag = Some(value);
+        self
+    }
+
+    /// Checks that the options are valid, as is done automatically before any request is made with them. In addition to the page size, this checks that the since filter is before the until filter.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if let Some(value) = self.page_size {
+            pagination::validate_page_size(value)?;
+        }
+
+        let since = self.filter_since.as_ref().map(|value| standard::parse_date_time("filter_since", value)).transpose()?;
+        let until = self.filter_until.as_ref().map(|value| standard::parse_date_time("filter_until", value)).transpose()?;
+
+        if let (Some(since), Some(until)) = (since, until) {
+            if since >= until {
+                return Err(error::Error::InvalidOptions(format!(
+                    "The since filter ({}) must be before the until filter ({}).",
+                    self.filter_since.as_ref().unwrap(),
+                    self.filter_until.as_ref().unwrap(),
+                )));
+            }
+        }
+
+        Ok(())
     }
 
     fn add_params(&self, url : &mut reqwest::Url) {
-        let mut query = String::new();
+        let mut query = Vec::new();
 
         if let Some(value) = &self.page_size {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("page[size]={}", value));
+            query.push(("page[size]", value.to_string()));
         }
 
         if let Some(value) = &self.filter_status {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[status]={}", value));
+            query.push(("filter[status]", value.to_string()));
         }
 
         if let Some(value) = &self.filter_since {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[since]={}", value));
+            query.push(("filter[since]", value.clone()));
         }
 
         if let Some(value) = &self.filter_until {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[until]={}", value));
+            query.push(("filter[until]", value.clone()));
         }
 
         if let Some(value) = &self.filter_category {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[category]={}", value));
+            query.push(("filter[category]", value.to_string()));
         }
 
         if let Some(value) = &self.filter_tag {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("filter[tag]={}", value));
+            query.push(("filter[tag]", value.to_string()));
         }
 
         if !query.is_empty() {
-            url.set_query(Some(&query));
+            url.query_pairs_mut().extend_pairs(query);
         }
     }
 }
@@ -291,90 +306,71 @@ impl ListTransactionsOptions {
 impl Client {
     /// Retrieve a list of all transactions across all accounts for the currently authenticated user. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present. To narrow the results to a specific date range pass one or both of `filter[since]` and `filter[until]` in the query string. These filter parameters should not be used for pagination. Results are ordered newest first to oldest last.
     pub async fn list_transactions(&self, options : &ListTransactionsOptions) -> Result<ListTransactionsResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/transactions", BASE_URL)).map_err(error::Error::UrlParse)?;
+        options.validate()?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/transactions", self.base_url)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let transaction_response : ListTransactionsResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let transaction_response : ListTransactionsResponse = error::from_json(&res.body)?;
 
                 Ok(transaction_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
+    /// Retrieve every transaction across all accounts for the currently authenticated user as a stream, following the `next` link of each page as it is needed. Results are ordered newest first to oldest last. The `limits` can be used to cap the total number of transactions or pages retrieved.
+    pub fn list_transactions_stream<'a>(&'a self, options : &ListTransactionsOptions, limits : pagination::StreamLimits) -> impl Stream<Item = Result<TransactionResource, error::Error>> + 'a {
+        let url = options
+            .validate()
+            .and_then(|_| reqwest::Url::parse(&format!("{}/transactions", self.base_url)).map_err(error::Error::UrlParse))
+            .map(|mut url| {
+                options.add_params(&mut url);
+                url
+            });
+
+        pagination::stream::<ListTransactionsResponse>(self, url, limits)
+    }
+
     /// Retrieve a specific transaction by providing its unique identifier.
-    pub async fn get_transaction(&self, id : &String) -> Result<GetTransactionResponse, error::Error> {
-        // This assertion is because without an ID the request is thought to be a request for
-        // many transactions, and therefore the error messages are very unclear.
-        if id.is_empty() {
-            panic!("The provided transaction ID must not be empty.");
-        }
+    pub async fn get_transaction(&self, id : &standard::TransactionId) -> Result<GetTransactionResponse, error::Error> {
+        validate_id("transaction ID", id.as_str())?;
 
-        let url = reqwest::Url::parse(&format!("{}/transactions/{}", BASE_URL, id)).map_err(error::Error::UrlParse)?;
+        let url = reqwest::Url::parse(&format!("{}/transactions/{}", self.base_url, id)).map_err(error::Error::UrlParse)?;
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let transaction_response : GetTransactionResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let transaction_response : GetTransactionResponse = error::from_json(&res.body)?;
 
                 Ok(transaction_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Retrieve a list of all transactions for a specific account. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present. To narrow the results to a specific date range pass one or both of `filter[since]` and `filter[until]` in the query string. These filter parameters should not be used for pagination. Results are ordered newest first to oldest last.
-    pub async fn list_transactions_by_account(&self, account_id : &String, options : &ListTransactionsOptions) -> Result<ListTransactionsResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/accounts/{}/transactions", BASE_URL, account_id)).map_err(error::Error::UrlParse)?;
+    pub async fn list_transactions_by_account(&self, account_id : &standard::AccountId, options : &ListTransactionsOptions) -> Result<ListTransactionsResponse, error::Error> {
+        validate_id("account ID", account_id.as_str())?;
+        options.validate()?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/accounts/{}/transactions", self.base_url, account_id)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let transaction_response : ListTransactionsResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let transaction_response : ListTransactionsResponse = error::from_json(&res.body)?;
 
                 Ok(transaction_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 }
@@ -382,4 +378,115 @@ impl Client {
 
 // ----------------- Page Navigation -----------------
 
-implement_pagination_v1!(ListTransactionsResponse);
+pagination::implement_paginated!(ListTransactionsResponse, TransactionResource);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_for(options : &ListTransactionsOptions) -> reqwest::Url {
+        let mut url = reqwest::Url::parse("https://api.up.com.au/api/v1/transactions").unwrap();
+        options.add_params(&mut url);
+        url
+    }
+
+    #[test]
+    fn no_query_without_options() {
+        let url = query_for(&ListTransactionsOptions::default());
+
+        assert_eq!(url.as_str(), "https://api.up.com.au/api/v1/transactions");
+    }
+
+    #[test]
+    fn tags_with_reserved_characters_are_encoded() {
+        let mut options = ListTransactionsOptions::default();
+        options.filter_tag(standard::TagId::from("Fish & Chips + Gravy"));
+        options.page_size(10);
+
+        let url = query_for(&options);
+
+        assert_eq!(url.query(), Some("page%5Bsize%5D=10&filter%5Btag%5D=Fish+%26+Chips+%2B+Gravy"));
+
+        let pairs : Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(pairs, vec![
+            (String::from("page[size]"), String::from("10")),
+            (String::from("filter[tag]"), String::from("Fish & Chips + Gravy")),
+        ]);
+    }
+
+    #[test]
+    fn offsets_in_date_times_are_encoded() {
+        let mut options = ListTransactionsOptions::default();
+        options.filter_since("2020-01-01T01:02:03+10:00");
+        options.filter_until("2020-02-01T00:00:00-05:30");
+
+        let url = query_for(&options);
+
+        assert_eq!(url.query(), Some("filter%5Bsince%5D=2020-01-01T01%3A02%3A03%2B10%3A00&filter%5Buntil%5D=2020-02-01T00%3A00%3A00-05%3A30"));
+
+        let since = url.query_pairs().find(|(key, _)| key == "filter[since]").unwrap().1;
+        assert_eq!(since, "2020-01-01T01:02:03+10:00");
+    }
+
+    #[test]
+    fn page_size_must_be_in_range() {
+        let mut options = ListTransactionsOptions::default();
+
+        assert!(matches!(options.page_size(0).validate(), Err(error::Error::InvalidOptions(_))));
+        assert!(matches!(options.page_size(101).validate(), Err(error::Error::InvalidOptions(_))));
+        assert!(options.page_size(100).validate().is_ok());
+        assert!(options.page_size(1).validate().is_ok());
+    }
+
+    #[test]
+    fn since_must_be_before_until() {
+        let mut options = ListTransactionsOptions::default();
+        options
+            .filter_status(standard::TransactionStatusEnum::Settled)
+            .filter_since("2020-01-01T10:00:00+10:00");
+
+        options.filter_until("2020-01-01T00:00:00Z");
+        assert!(matches!(options.validate(), Err(error::Error::InvalidOptions(_))));
+
+        options.filter_until("2020-01-01T00:00:00.5Z");
+        assert!(options.validate().is_ok());
+
+        options.filter_until("2019-12-31T23:59:59-00:30");
+        assert!(options.validate().is_ok());
+    }
+
+    #[test]
+    fn malformed_date_times_are_rejected() {
+        for value in ["2020-01-01", "2020-01-01T00:00:00", "2020-13-01T00:00:00Z", "2020-01-01T00:00:00+1000", "2020-01-01T00:00:00+99:99", "2020-02-31T00:00:00Z", "2021-02-29T00:00:00Z", "2021-04-31T00:00:00Z"] {
+            let mut options = ListTransactionsOptions::default();
+            options.filter_since(value);
+
+            assert!(matches!(options.validate(), Err(error::Error::InvalidOptions(_))), "{}", value);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_times_can_be_used() {
+        let since = chrono::DateTime::parse_from_rfc3339("2020-01-01T01:02:03+10:00").unwrap();
+        let until = since.with_timezone(&chrono::Utc) + chrono::Duration::milliseconds(1500);
+
+        let mut options = ListTransactionsOptions::default();
+        options.filter_since_chrono(&since).filter_until_chrono(&until);
+
+        let pairs : Vec<(String, String)> = query_for(&options).query_pairs().into_owned().collect();
+        assert_eq!(pairs, vec![
+            (String::from("filter[since]"), String::from("2020-01-01T01:02:03+10:00")),
+            (String::from("filter[until]"), String::from("2019-12-31T15:02:04.500Z")),
+        ]);
+        assert!(options.validate().is_ok());
+
+        let resource = crate::v1::memory::transaction("transaction-1", "account-1", "Coffee", -450, standard::TransactionStatusEnum::Held, "2020-01-01T01:02:03+10:00");
+        let attributes : Attributes = serde_json::from_value(resource["attributes"].clone()).unwrap();
+
+        assert_eq!(attributes.created_at.as_str(), "2020-01-01T01:02:03+10:00");
+        assert_eq!(attributes.created_at.to_chrono(), since);
+        assert_eq!(attributes.created_at.to_chrono().offset().local_minus_utc(), 36000);
+        assert!(attributes.settled_at.is_none());
+    }
+}
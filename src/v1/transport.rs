@@ -0,0 +1,103 @@
+use crate::v1::error;
+
+use async_trait::async_trait;
+
+/// A HTTP request to be sent to the API.
+#[derive(Clone, Debug)]
+pub struct Request {
+    /// The HTTP method of the request.
+    pub method : reqwest::Method,
+    /// The full URL of the request, including any query parameters.
+    pub url : reqwest::Url,
+    /// The headers of the request, including the `Authorization` header.
+    pub headers : reqwest::header::HeaderMap,
+    /// The JSON body of the request, if it has one.
+    pub body : Option<String>,
+}
+
+/// A HTTP response received from the API, with the body already read.
+#[derive(Clone, Debug)]
+pub struct Response {
+    /// The HTTP status code of the response.
+    pub status : reqwest::StatusCode,
+    /// The headers of the response.
+    pub headers : reqwest::header::HeaderMap,
+    /// The body of the response, which is empty for `204` responses.
+    pub body : String,
+}
+
+impl Response {
+    /// Creates a response with the given status code and JSON body.
+    pub fn json(status : reqwest::StatusCode, body : impl Into<String>) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::CONTENT_TYPE, reqwest::header::HeaderValue::from_static("application/json"));
+
+        Response {
+            status,
+            headers,
+            body : body.into(),
+        }
+    }
+
+    /// Creates a response with the given status code and no body.
+    pub fn empty(status : reqwest::StatusCode) -> Self {
+        Response {
+            status,
+            headers : reqwest::header::HeaderMap::new(),
+            body : String::new(),
+        }
+    }
+
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name : reqwest::header::HeaderName, value : reqwest::header::HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+}
+
+/// The means by which a `Client` sends requests to the API. The default `ReqwestTransport` makes real HTTP requests, while the `MockTransport` can be used to test code which uses a `Client` without a network.
+#[async_trait]
+pub trait Transport : Send + Sync {
+    /// Sends a single request, returning the response regardless of its status code.
+    async fn send(&self, request : Request) -> Result<Response, error::Error>;
+}
+
+/// A `Transport` which makes HTTP requests using a (pooled) `reqwest::Client`.
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client : reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Creates the transport from a configured `reqwest::Client`.
+    pub fn new(client : reqwest::Client) -> Self {
+        ReqwestTransport {
+            client
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request : Request) -> Result<Response, error::Error> {
+        let mut builder = self.client
+            .request(request.method, request.url)
+            .headers(request.headers);
+
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let res = builder.send().await.map_err(error::Error::Request)?;
+
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = res.text().await.map_err(error::Error::BodyRead)?;
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
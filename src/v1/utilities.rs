@@ -1,4 +1,4 @@
-use crate::v1::{Client, error, BASE_URL};
+use crate::v1::{Client, error};
 
 use serde::Deserialize;
 
@@ -21,29 +21,17 @@ pub struct Meta {
 impl Client {
     /// Make a basic ping request to the API. This is useful to verify that authentication is functioning correctly.
     pub async fn ping(&self) -> Result<PingResponse, error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/util/ping", BASE_URL)).map_err(error::Error::UrlParse)?;
+        let url = reqwest::Url::parse(&format!("{}/util/ping", self.base_url)).map_err(error::Error::UrlParse)?;
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                println!("{}", body);
-                let ping_response : PingResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let ping_response : PingResponse = error::from_json(&res.body)?;
 
                 Ok(ping_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 }
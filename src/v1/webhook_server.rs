@@ -0,0 +1,642 @@
+use crate::v1::{api, error, event_store, standard, transactions};
+use crate::v1::webhooks::{Event, WebhookEventPayload, SIGNATURE_HEADER};
+
+use std::convert::Infallible;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use reqwest::StatusCode;
+
+// ----------------- Handlers -----------------
+
+/// Handles the events delivered to a webhook, with one method per type of event. Each method does nothing by default, so only the events of interest need to be handled.
+///
+/// Returning an error responds to Up with the error's status code rather than `200`, which Up records in the webhook's delivery logs as `BAD_RESPONSE_CODE` before retrying the delivery. As such a handler should only succeed once the event has been fully processed.
+#[async_trait]
+pub trait WebhookHandler : Send + Sync {
+    /// Handles a `TRANSACTION_CREATED` event, sent when a new transaction is created (possibly in the `HELD` status).
+    async fn transaction_created(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = (transaction_id, payload);
+        Ok(())
+    }
+
+    /// Handles a `TRANSACTION_SETTLED` event, sent when a transaction moves from the `HELD` status to the `SETTLED` status.
+    async fn transaction_settled(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = (transaction_id, payload);
+        Ok(())
+    }
+
+    /// Handles a `TRANSACTION_DELETED` event, sent when a `HELD` transaction is deleted. The transaction can no longer be retrieved.
+    async fn transaction_deleted(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = (transaction_id, payload);
+        Ok(())
+    }
+
+    /// Handles a `PING` event, sent by `ping_webhook`.
+    async fn ping(&self, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = payload;
+        Ok(())
+    }
+}
+
+/// A failure to handle a webhook event, along with the status code to respond to Up with.
+#[derive(Debug)]
+pub struct HandlerError {
+    status : StatusCode,
+    message : String,
+}
+
+impl HandlerError {
+    /// Creates an error which responds with the given status code. This must be a client or server error status code, as Up would consider the event delivered after a success status code, so any other status code is replaced with `500 Internal Server Error`.
+    pub fn new(status : StatusCode, message : impl Into<String>) -> Self {
+        let status = if status.is_client_error() || status.is_server_error() { status } else { StatusCode::INTERNAL_SERVER_ERROR };
+
+        HandlerError {
+            status,
+            message : message.into(),
+        }
+    }
+
+    /// Creates an error for a temporary failure, such as an unavailable database, which responds with `503 Service Unavailable`.
+    pub fn unavailable(message : impl Into<String>) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, message)
+    }
+
+    /// Creates an error for any other failure, which responds with `500 Internal Server Error`.
+    pub fn internal(message : impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    /// The status code responded with.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The description of the failure.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Failed to handle the webhook event ({}): {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for HandlerError {}
+
+/// Errors from requests to the API made while handling an event respond with `503 Service Unavailable` if they may succeed when retried, and `500 Internal Server Error` otherwise.
+impl From<error::Error> for HandlerError {
+    fn from(err : error::Error) -> Self {
+        if err.is_retryable() {
+            Self::unavailable(err.to_string())
+        }
+        else {
+            Self::internal(err.to_string())
+        }
+    }
+}
+
+// ----------------- Hydration -----------------
+
+/// Handles the events delivered to a webhook as with `WebhookHandler`, but receiving the full transaction for created and settled events rather than just its ID. Used with a `Hydrator`.
+#[async_trait]
+pub trait TransactionHandler : Send + Sync {
+    /// Handles a `TRANSACTION_CREATED` event, with the transaction as retrieved when the event was received.
+    async fn transaction_created(&self, transaction : &transactions::TransactionResource, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = (transaction, payload);
+        Ok(())
+    }
+
+    /// Handles a `TRANSACTION_SETTLED` event, with the transaction as retrieved when the event was received.
+    async fn transaction_settled(&self, transaction : &transactions::TransactionResource, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = (transaction, payload);
+        Ok(())
+    }
+
+    /// Handles a `TRANSACTION_DELETED` event. Only the ID is available, as the transaction can no longer be retrieved.
+    async fn transaction_deleted(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = (transaction_id, payload);
+        Ok(())
+    }
+
+    /// Handles a `PING` event, sent by `ping_webhook`.
+    async fn ping(&self, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let _ = payload;
+        Ok(())
+    }
+}
+
+/// A `WebhookHandler` which retrieves the transaction of each created or settled event before passing it to a `TransactionHandler`.
+///
+/// A transaction is occasionally not visible through the API as soon as its event is sent, so a `404` response is retried a few times after a short delay. If the transaction still cannot be found the event is responded to with `503 Service Unavailable`, such that Up delivers it again later.
+///
+/// ```no_run
+/// use up_api::v1::Client;
+/// use up_api::v1::transactions::TransactionResource;
+/// use up_api::v1::webhooks::WebhookEventPayload;
+/// use up_api::v1::webhook_server::{Dispatcher, HandlerError, Hydrator, TransactionHandler};
+///
+/// use std::sync::Arc;
+///
+/// struct Printer;
+///
+/// #[async_trait::async_trait]
+/// impl TransactionHandler for Printer {
+///     async fn transaction_created(&self, transaction : &TransactionResource, _payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+///         println!("{}: {}", transaction.attributes.description, transaction.attributes.amount.value);
+///         Ok(())
+///     }
+/// }
+///
+/// let client = Client::new(String::from("..."));
+/// let dispatcher = Dispatcher::new(String::from("secret key"), Arc::new(Hydrator::new(Arc::new(client), Arc::new(Printer))));
+/// ```
+pub struct Hydrator {
+    client : Arc<dyn api::UpApi>,
+    handler : Arc<dyn TransactionHandler>,
+    not_found_retries : u32,
+    not_found_delay : Duration,
+}
+
+impl Hydrator {
+    /// Creates a `Hydrator` which retrieves transactions with the given client, retrying a `404` response up to 3 times at intervals of 1 second.
+    pub fn new(client : Arc<dyn api::UpApi>, handler : Arc<dyn TransactionHandler>) -> Self {
+        Hydrator {
+            client,
+            handler,
+            not_found_retries : 3,
+            not_found_delay : Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the number of times a transaction which is not found is requested again.
+    pub fn not_found_retries(mut self, value : u32) -> Self {
+        self.not_found_retries = value;
+        self
+    }
+
+    /// Sets the delay before a transaction which is not found is requested again.
+    pub fn not_found_delay(mut self, value : Duration) -> Self {
+        self.not_found_delay = value;
+        self
+    }
+
+    async fn get_transaction(&self, id : &standard::TransactionId) -> Result<transactions::TransactionResource, HandlerError> {
+        let mut retries = 0;
+
+        loop {
+            match self.client.get_transaction(id).await {
+                Ok(response) => return Ok(response.data),
+                Err(err) if err.is_not_found() && retries < self.not_found_retries => {
+                    retries += 1;
+                    tokio::time::sleep(self.not_found_delay).await;
+                },
+                Err(err) if err.is_not_found() => {
+                    return Err(HandlerError::unavailable(format!("The transaction {} could not be found.", id)));
+                },
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookHandler for Hydrator {
+    async fn transaction_created(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let transaction = self.get_transaction(transaction_id).await?;
+        self.handler.transaction_created(&transaction, payload).await
+    }
+
+    async fn transaction_settled(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        let transaction = self.get_transaction(transaction_id).await?;
+        self.handler.transaction_settled(&transaction, payload).await
+    }
+
+    async fn transaction_deleted(&self, transaction_id : &standard::TransactionId, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        self.handler.transaction_deleted(transaction_id, payload).await
+    }
+
+    async fn ping(&self, payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+        self.handler.ping(payload).await
+    }
+}
+
+// ----------------- Dispatching -----------------
+
+/// Verifies, parses and dispatches webhook requests to a `WebhookHandler`, independently of any HTTP server. This is used by `WebhookServer`, and can be used directly to receive webhooks within an existing server.
+///
+/// Requests respond with:
+/// - `401 Unauthorized` if the signature is missing or does not match the body.
+/// - `400 Bad Request` if the body is not a webhook event.
+/// - `503 Service Unavailable` if the `EventStore` fails to be read.
+/// - The status of the `HandlerError` if the handler fails.
+/// - `200 OK` otherwise, including for events skipped as already processed and events of a type unknown to this crate (which are not passed to the handler).
+#[derive(Clone)]
+pub struct Dispatcher {
+    secret_key : String,
+    handler : Arc<dyn WebhookHandler>,
+    event_store : Option<Arc<dyn event_store::EventStore>>,
+}
+
+impl Dispatcher {
+    /// Creates a `Dispatcher` which verifies requests with the webhook's secret key (as returned by `create_webhook`) before passing them to the handler.
+    pub fn new(secret_key : String, handler : Arc<dyn WebhookHandler>) -> Self {
+        Dispatcher {
+            secret_key,
+            handler,
+            event_store : None,
+        }
+    }
+
+    /// Sets a store of processed event IDs, which is consulted to skip events that have already been handled successfully, such as when Up retries a delivery after a timeout.
+    ///
+    /// Events are recorded once their handler succeeds, so an event redelivered while it is still being handled may be handled twice.
+    pub fn event_store(mut self, value : Arc<dyn event_store::EventStore>) -> Self {
+        self.event_store = Some(value);
+        self
+    }
+
+    /// Dispatches a webhook request, given its raw body and the value of its `X-Up-Authenticity-Signature` header, returning the status code to respond with.
+    pub async fn dispatch(&self, body : &[u8], signature : Option<&str>) -> StatusCode {
+        let payload = match WebhookEventPayload::verify(body, signature, &self.secret_key) {
+            Ok(payload) => payload,
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %err, "rejected webhook request");
+
+                return if err.is_verification() { StatusCode::UNAUTHORIZED } else { StatusCode::BAD_REQUEST };
+            },
+        };
+
+        // This check and the insert below are not atomic, so skipping is best-effort for deliveries which overlap (see `EventStore`).
+        if let Some(store) = &self.event_store {
+            match store.contains(payload.id()).await {
+                Ok(true) => return StatusCode::OK,
+                Ok(false) => {},
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(event = payload.id(), error = %_err, "failed to read the event store");
+
+                    return StatusCode::SERVICE_UNAVAILABLE;
+                },
+            }
+        }
+
+        let result = match &payload.event {
+            Event::TransactionCreated(id) => self.handler.transaction_created(id, &payload).await,
+            Event::TransactionSettled(id) => self.handler.transaction_settled(id, &payload).await,
+            Event::TransactionDeleted(id) => self.handler.transaction_deleted(id, &payload).await,
+            Event::Ping => self.handler.ping(&payload).await,
+            // Up would keep retrying an event which is not acknowledged, even though it can never be handled.
+            Event::Unknown => Ok(()),
+        };
+
+        match result {
+            Ok(()) => {
+                if let Some(store) = &self.event_store {
+                    // The event has been handled, so a failure to record it only risks handling it again.
+                    if let Err(_err) = store.insert(payload.id()).await {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(event = payload.id(), error = %_err, "failed to record the event as processed");
+                    }
+                }
+
+                StatusCode::OK
+            },
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(event = payload.id(), error = %err, "failed to handle webhook event");
+
+                err.status()
+            },
+        }
+    }
+}
+
+// ----------------- Server -----------------
+
+/// The maximum size in bytes of the body of a request to a `WebhookServer`, which is well above that of any webhook event. Larger requests are rejected with `413 Payload Too Large` before their signature is checked.
+pub const MAX_BODY_SIZE : usize = 64 * 1024;
+
+/// An HTTP server which receives webhook events on a local address, passing each `POST` request (to any path) to a `Dispatcher`. The server stops when it is dropped.
+///
+/// ```no_run
+/// use up_api::v1::standard::TransactionId;
+/// use up_api::v1::webhooks::WebhookEventPayload;
+/// use up_api::v1::webhook_server::{Dispatcher, HandlerError, WebhookHandler, WebhookServer};
+///
+/// use std::sync::Arc;
+///
+/// struct Printer;
+///
+/// #[async_trait::async_trait]
+/// impl WebhookHandler for Printer {
+///     async fn transaction_created(&self, transaction_id : &TransactionId, _payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+///         println!("Created {}", transaction_id);
+///         Ok(())
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let dispatcher = Dispatcher::new(String::from("secret key"), Arc::new(Printer));
+/// let _server = WebhookServer::start(([0, 0, 0, 0], 8080).into(), dispatcher).await.unwrap();
+///
+/// // The server stops when it is dropped, so keep it until the program is stopped.
+/// std::future::pending::<()>().await;
+/// # }
+/// ```
+pub struct WebhookServer {
+    address : SocketAddr,
+    shutdown : Option<oneshot::Sender<()>>,
+}
+
+impl WebhookServer {
+    /// Starts the server on the given address, which may have a port of `0` to use any free port. This must be called within a Tokio runtime.
+    pub async fn start(address : SocketAddr, dispatcher : Dispatcher) -> Result<Self, error::Error> {
+        let start_error = |err : &dyn fmt::Display| error::Error::Transport(format!("Failed to start the webhook server: {}", err));
+
+        let listener = std::net::TcpListener::bind(address).map_err(|err| start_error(&err))?;
+        listener.set_nonblocking(true).map_err(|err| start_error(&err))?;
+        let address = listener.local_addr().map_err(|err| start_error(&err))?;
+
+        let make_service = hyper::service::make_service_fn(move |_| {
+            let dispatcher = dispatcher.clone();
+
+            async move {
+                Ok::<_, Infallible>(hyper::service::service_fn(move |req| {
+                    let dispatcher = dispatcher.clone();
+
+                    async move {
+                        Ok::<_, Infallible>(handle(&dispatcher, req).await)
+                    }
+                }))
+            }
+        });
+
+        let (sender, receiver) = oneshot::channel::<()>();
+
+        let server = hyper::Server::from_tcp(listener).map_err(|err| start_error(&err))?.serve(make_service);
+
+        tokio::spawn(server.with_graceful_shutdown(async {
+            receiver.await.ok();
+        }));
+
+        Ok(WebhookServer {
+            address,
+            shutdown : Some(sender),
+        })
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+impl Drop for WebhookServer {
+    fn drop(&mut self) {
+        if let Some(sender) = self.shutdown.take() {
+            sender.send(()).ok();
+        }
+    }
+}
+
+async fn handle(dispatcher : &Dispatcher, req : hyper::Request<hyper::Body>) -> hyper::Response<hyper::Body> {
+    let status = if req.method() != hyper::Method::POST {
+        StatusCode::METHOD_NOT_ALLOWED
+    }
+    else {
+        let (parts, body) = req.into_parts();
+        let signature = parts.headers.get(SIGNATURE_HEADER).and_then(|value| value.to_str().ok());
+
+        let content_length =
+            parts.headers
+            .get(hyper::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if content_length.is_some_and(|length| length > MAX_BODY_SIZE as u64) {
+            StatusCode::PAYLOAD_TOO_LARGE
+        }
+        else {
+            match read_body(body).await {
+                Ok(body) => dispatcher.dispatch(&body, signature).await,
+                Err(status) => status,
+            }
+        }
+    };
+
+    let mut response = hyper::Response::new(hyper::Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+/// Reads the body of a request, failing once it exceeds `MAX_BODY_SIZE` such that chunked bodies without a `Content-Length` are limited too.
+async fn read_body(mut body : hyper::Body) -> Result<Vec<u8>, StatusCode> {
+    use hyper::body::HttpBody;
+
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::{memory, test_util};
+    use crate::v1::event_store::EventStore;
+    use crate::v1::mock::MockTransport;
+    use crate::v1::test_util::{SECRET, event, sign};
+    use crate::v1::transport::Response;
+
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        events : Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl WebhookHandler for Recorder {
+        async fn transaction_created(&self, transaction_id : &standard::TransactionId, _payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+            if transaction_id.as_str() == "unavailable" {
+                return Err(HandlerError::unavailable("The database is down."));
+            }
+
+            self.events.lock().unwrap().push(format!("created {}", transaction_id));
+            Ok(())
+        }
+
+        async fn transaction_deleted(&self, transaction_id : &standard::TransactionId, _payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+            self.events.lock().unwrap().push(format!("deleted {}", transaction_id));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn events_are_dispatched_by_type() {
+        let recorder = Arc::new(Recorder::default());
+        let dispatcher = Dispatcher::new(String::from(SECRET), recorder.clone());
+
+        let created = event("event-1", "TRANSACTION_CREATED", Some("transaction-1"));
+        let settled = event("event-2", "TRANSACTION_SETTLED", Some("transaction-1"));
+        let deleted = event("event-3", "TRANSACTION_DELETED", Some("transaction-2"));
+        let unavailable = event("event-4", "TRANSACTION_CREATED", Some("unavailable"));
+
+        assert_eq!(dispatcher.dispatch(created.as_bytes(), Some(&sign(&created))).await, StatusCode::OK);
+        assert_eq!(dispatcher.dispatch(settled.as_bytes(), Some(&sign(&settled))).await, StatusCode::OK);
+        assert_eq!(dispatcher.dispatch(deleted.as_bytes(), Some(&sign(&deleted))).await, StatusCode::OK);
+        assert_eq!(dispatcher.dispatch(unavailable.as_bytes(), Some(&sign(&unavailable))).await, StatusCode::SERVICE_UNAVAILABLE);
+
+        assert_eq!(dispatcher.dispatch(created.as_bytes(), Some(&sign(&settled))).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(dispatcher.dispatch(created.as_bytes(), None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(dispatcher.dispatch(b"{}", Some(&sign("{}"))).await, StatusCode::BAD_REQUEST);
+
+        let unknown = event("event-5", "ACCOUNT_CREATED", None);
+        assert_eq!(dispatcher.dispatch(unknown.as_bytes(), Some(&sign(&unknown))).await, StatusCode::OK);
+
+        assert_eq!(*recorder.events.lock().unwrap(), vec!["created transaction-1", "deleted transaction-2"]);
+    }
+
+    #[test]
+    fn handler_errors_never_respond_with_success() {
+        assert_eq!(HandlerError::new(StatusCode::CONFLICT, "").status(), StatusCode::CONFLICT);
+        assert_eq!(HandlerError::new(StatusCode::BAD_GATEWAY, "").status(), StatusCode::BAD_GATEWAY);
+
+        for status in [StatusCode::OK, StatusCode::NO_CONTENT, StatusCode::MOVED_PERMANENTLY, StatusCode::CONTINUE] {
+            assert_eq!(HandlerError::new(status, "").status(), StatusCode::INTERNAL_SERVER_ERROR, "{}", status);
+        }
+    }
+
+    #[tokio::test]
+    async fn redelivered_events_are_skipped() {
+        let recorder = Arc::new(Recorder::default());
+        let store = Arc::new(event_store::InMemoryEventStore::new(Duration::from_secs(3600)));
+        let dispatcher = Dispatcher::new(String::from(SECRET), recorder.clone()).event_store(store.clone());
+
+        let created = event("event-1", "TRANSACTION_CREATED", Some("transaction-1"));
+        let unavailable = event("event-2", "TRANSACTION_CREATED", Some("unavailable"));
+
+        for _ in 0..2 {
+            assert_eq!(dispatcher.dispatch(created.as_bytes(), Some(&sign(&created))).await, StatusCode::OK);
+            assert_eq!(dispatcher.dispatch(unavailable.as_bytes(), Some(&sign(&unavailable))).await, StatusCode::SERVICE_UNAVAILABLE);
+        }
+
+        assert_eq!(*recorder.events.lock().unwrap(), vec!["created transaction-1"]);
+        assert!(store.contains("event-1").await.unwrap());
+        assert!(!store.contains("event-2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn transactions_are_hydrated_once_visible() {
+        #[derive(Default)]
+        struct Descriptions {
+            seen : Mutex<Vec<String>>,
+        }
+
+        #[async_trait]
+        impl TransactionHandler for Descriptions {
+            async fn transaction_created(&self, transaction : &transactions::TransactionResource, _payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+                self.seen.lock().unwrap().push(transaction.attributes.description.clone());
+                Ok(())
+            }
+
+            async fn transaction_deleted(&self, transaction_id : &standard::TransactionId, _payload : &WebhookEventPayload) -> Result<(), HandlerError> {
+                self.seen.lock().unwrap().push(format!("deleted {}", transaction_id));
+                Ok(())
+            }
+        }
+
+        let not_found = Response::json(reqwest::StatusCode::NOT_FOUND, r#"{"errors":[{"status":"404","title":"Not Found","detail":"Not found."}]}"#);
+        let found = serde_json::json!({ "data" : memory::transaction("transaction-9", "spending", "Coffee", -450, standard::TransactionStatusEnum::Held, "2024-01-01T09:30:00+10:00") });
+
+        let mock = Arc::new(MockTransport::new());
+        mock.register(reqwest::Method::GET, "/transactions/transaction-9", not_found.clone());
+        mock.register(reqwest::Method::GET, "/transactions/transaction-9", Response::json(reqwest::StatusCode::OK, found.to_string()));
+        mock.register(reqwest::Method::GET, "/transactions/missing", not_found);
+
+        let client = test_util::client(mock.clone());
+        let descriptions = Arc::new(Descriptions::default());
+        let hydrator = Hydrator::new(Arc::new(client), descriptions.clone()).not_found_retries(2).not_found_delay(Duration::ZERO);
+        let dispatcher = Dispatcher::new(String::from(SECRET), Arc::new(hydrator));
+
+        let created = event("event-1", "TRANSACTION_CREATED", Some("transaction-9"));
+        let deleted = event("event-2", "TRANSACTION_DELETED", Some("transaction-8"));
+        let missing = event("event-3", "TRANSACTION_SETTLED", Some("missing"));
+
+        assert_eq!(dispatcher.dispatch(created.as_bytes(), Some(&sign(&created))).await, StatusCode::OK);
+        assert_eq!(dispatcher.dispatch(deleted.as_bytes(), Some(&sign(&deleted))).await, StatusCode::OK);
+        assert_eq!(dispatcher.dispatch(missing.as_bytes(), Some(&sign(&missing))).await, StatusCode::SERVICE_UNAVAILABLE);
+
+        assert_eq!(*descriptions.seen.lock().unwrap(), vec!["Coffee", "deleted transaction-8"]);
+        assert_eq!(mock.requests_to(reqwest::Method::GET, "/transactions/transaction-9").len(), 2);
+        assert_eq!(mock.requests_to(reqwest::Method::GET, "/transactions/missing").len(), 3);
+        mock.assert_not_requested(reqwest::Method::GET, "/transactions/transaction-8");
+    }
+
+    #[tokio::test]
+    async fn server_responds_with_dispatched_status() {
+        let recorder = Arc::new(Recorder::default());
+        let server = WebhookServer::start(([127, 0, 0, 1], 0).into(), Dispatcher::new(String::from(SECRET), recorder.clone())).await.unwrap();
+        let url = format!("http://{}/webhook", server.local_addr());
+
+        let ping = event("event-1", "PING", None);
+        let http = reqwest::Client::new();
+
+        let response = http.post(&url).header(SIGNATURE_HEADER, sign(&ping)).body(ping.clone()).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = http.post(&url).header(SIGNATURE_HEADER, "00").body(ping).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        assert_eq!(http.get(&url).send().await.unwrap().status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_rejected() {
+        let recorder = Arc::new(Recorder::default());
+        let dispatcher = Dispatcher::new(String::from(SECRET), recorder.clone());
+        let server = WebhookServer::start(([127, 0, 0, 1], 0).into(), dispatcher.clone()).await.unwrap();
+        let url = format!("http://{}/webhook", server.local_addr());
+
+        let padded = event("event-1", "PING", None) + &" ".repeat(MAX_BODY_SIZE);
+        let response = reqwest::Client::new().post(&url).header(SIGNATURE_HEADER, sign(&padded)).body(padded.clone()).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // A chunked body has no Content-Length, so is only rejected once too much of it has been read.
+        let (mut sender, body) = hyper::Body::channel();
+        let request = hyper::Request::post("/webhook").header(SIGNATURE_HEADER, sign(&padded)).body(body).unwrap();
+
+        tokio::spawn(async move {
+            for chunk in padded.into_bytes().chunks(1024) {
+                if sender.send_data(hyper::body::Bytes::copy_from_slice(chunk)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        assert_eq!(handle(&dispatcher, request).await.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(recorder.events.lock().unwrap().is_empty());
+
+        let ping = event("event-2", "PING", None);
+        let request = hyper::Request::post("/webhook").header(SIGNATURE_HEADER, sign(&ping)).body(hyper::Body::from(ping)).unwrap();
+        assert_eq!(handle(&dispatcher, request).await.status(), StatusCode::OK);
+    }
+}
@@ -1,5 +1,7 @@
-use crate::v1::{Client, error, BASE_URL, standard};
+use crate::v1::{Client, error, pagination, standard, validate_id};
 
+use futures::Stream;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 
 // ----------------- Response Objects -----------------
@@ -8,7 +10,7 @@ use serde::{Deserialize, Serialize};
 pub struct ListWebhooksResponse {
     /// The list of webhooks returned in this response.
     pub data : Vec<WebhookResource>,
-    pub links : ResponseLinks,
+    pub links : pagination::PageLinks,
 }
 
 #[derive(Deserialize, Debug)]
@@ -28,7 +30,7 @@ pub struct WebhookResource {
     /// The type of this resource: `webhooks`
     pub r#type : String,
     /// The unique identifier for this webhook.
-    pub id : String,
+    pub id : standard::WebhookId,
     pub attributes : Attributes,
     pub relationships : Relationships,
     pub links : WebhookResourceLinks,
@@ -45,7 +47,7 @@ pub struct Attributes {
     /// The webhook URL receives a request with a `X-Up-Authenticity-Signature` header, which is the SHA-256 HMAC of the entire raw request body signed using this `secretKey`. It is advised to compute and check this signature to verify the authenticity of requests sent to the webhook URL. See Handling webhook events for full details.
     pub secret_key : Option<String>,
     /// The date-time at which this webhook was created.
-    pub created_at : String,
+    pub created_at : standard::DateTime,
 }
 
 #[derive(Deserialize, Debug)]
@@ -71,14 +73,6 @@ pub struct WebhookResourceLinks {
     pub this : String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ResponseLinks {
-    /// The link to the previous page in the results. If this value is `None` there is no previous page.
-    pub prev : Option<String>,
-    /// The link to the next page in the results. If this value is `None` there is no next page.
-    pub next : Option<String>,
-}
-
 #[derive(Deserialize, Debug)]
 pub struct PingWebhookResponse {
     /// The webhook event data sent to the subscribed webhook.
@@ -118,7 +112,7 @@ pub struct WebhookData {
     /// The type of this resource: `webhooks`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::WebhookId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -132,7 +126,7 @@ pub struct TransactionData {
     /// The type of this resource: `transactions`
     pub r#type : String,
     /// The unique identifier of the resource within its type.
-    pub id : String,
+    pub id : standard::TransactionId,
 }
 
 #[derive(Deserialize, Debug)]
@@ -147,7 +141,7 @@ pub struct EventAttributes {
     /// The type of this event. This can be used to determine what action to take in response to the event.
     pub event_type : standard::WebhookEventTypeEnum,
     /// The date-time at which this event was generated.
-    pub created_at : String,
+    pub created_at : standard::DateTime,
 }
 
 
@@ -155,7 +149,7 @@ pub struct EventAttributes {
 pub struct ListWebhookLogsResponse {
     /// The list of delivery logs returned in this response.
     pub data : Vec<WebhookDeliveryLogResource>,
-    pub links : LogsResponseLinks,
+    pub links : pagination::PageLinks,
 }
 
 #[derive(Deserialize, Debug)]
@@ -197,7 +191,7 @@ pub struct DeliveryLogAttributes {
     /// The success or failure status of this delivery attempt.
     pub delivery_status : standard::WebhookDeliveryStatusEnum,
     /// The date-time at which this log entry was created.
-    pub created_at : String,
+    pub created_at : standard::DateTime,
 }
 
 #[derive(Deserialize, Debug)]
@@ -215,12 +209,121 @@ pub struct Response {
     pub body : String,
 }
 
+
+// ----------------- Inbound Events -----------------
+
+/// The body of a webhook event, as `POST`ed by Up to the configured webhook URL. Use `parse` to read it from the raw request body.
 #[derive(Deserialize, Debug)]
-pub struct LogsResponseLinks {
-    /// The link to the previous page in the results. If this value is `None` there is no previous page.
-    pub prev : Option<String>,
-    /// The link to the next page in the results. If this value is `None` there is no next page.
-    pub next : Option<String>,
+#[serde(try_from = "EventDocument")]
+pub struct WebhookEventPayload {
+    /// The webhook event resource, as would be returned by `ping_webhook`.
+    pub data : WebhookEventResource,
+    /// The event described by the payload.
+    pub event : Event,
+}
+
+/// A webhook event by type, along with the transaction it relates to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A new transaction has been created, possibly in the `HELD` status.
+    TransactionCreated(standard::TransactionId),
+    /// A transaction has moved from the `HELD` status to the `SETTLED` status.
+    TransactionSettled(standard::TransactionId),
+    /// A `HELD` transaction has been deleted, such as when a hold is cancelled. The transaction can no longer be retrieved.
+    TransactionDeleted(standard::TransactionId),
+    /// A `PING` event sent with `ping_webhook`, which relates to no transaction.
+    Ping,
+    /// An event of a type added to the API since this version of the crate. Its type is not known, so any transaction it relates to is not read.
+    Unknown,
+}
+
+impl Event {
+    /// The ID of the transaction the event relates to, which is `None` only for `Ping` and `Unknown` events.
+    pub fn transaction_id(&self) -> Option<&standard::TransactionId> {
+        match self {
+            Event::TransactionCreated(id) | Event::TransactionSettled(id) | Event::TransactionDeleted(id) => Some(id),
+            Event::Ping | Event::Unknown => None,
+        }
+    }
+}
+
+impl WebhookEventPayload {
+    /// Parses the raw body of a webhook request, failing if it is not a webhook event or is a transaction event without a related transaction.
+    ///
+    /// This does not check the `X-Up-Authenticity-Signature` header, so the payload should only be trusted once its signature has been verified.
+    pub fn parse(body : &str) -> Result<Self, error::Error> {
+        error::from_json(body)
+    }
+
+    /// The unique identifier of the event, which remains constant across delivery retries.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+
+    /// Verifies the signature of a webhook request with `verify_signature`, then parses the body. The `signature` is the value of the `X-Up-Authenticity-Signature` header, if present, and the `secret_key` is that returned when the webhook was created.
+    ///
+    /// ```
+    /// use up_api::v1::webhooks::{Event, WebhookEventPayload};
+    ///
+    /// let body = br#"{"data":{"type":"webhook-events","id":"ping-event","attributes":{"eventType":"PING","createdAt":"2024-01-01T09:30:00+10:00"},"relationships":{"webhook":{"data":{"type":"webhooks","id":"webhook-1"}}}}}"#;
+    /// let signature = "d5492f004d6b20d74773fd2d892f4d230881503739c07d28adb2362ec27bddd8";
+    ///
+    /// let payload = WebhookEventPayload::verify(body, Some(signature), "n8cEPk2sJzXb7QfKvHrT").unwrap();
+    /// assert_eq!(payload.event, Event::Ping);
+    ///
+    /// assert!(WebhookEventPayload::verify(body, Some(signature), "another-secret").unwrap_err().is_verification());
+    /// ```
+    pub fn verify(body : &[u8], signature : Option<&str>, secret_key : &str) -> Result<Self, error::Error> {
+        let signature = signature.ok_or(error::Error::Verification(error::VerificationError::MissingSignature))?;
+        verify_signature(body, signature, secret_key)?;
+        error::from_json_bytes(body)
+    }
+}
+
+/// The header of each webhook request which holds the signature of the body.
+pub const SIGNATURE_HEADER : &str = "X-Up-Authenticity-Signature";
+
+/// Checks that the signature from the `X-Up-Authenticity-Signature` header of a webhook request is the hex encoded SHA-256 HMAC of the raw body, signed with the webhook's secret key. The comparison is made in constant time.
+///
+/// The body must be exactly as received, before any parsing or re-encoding.
+pub fn verify_signature(body : &[u8], signature : &str, secret_key : &str) -> Result<(), error::Error> {
+    let signature = hex::decode(signature.trim()).map_err(|_| error::Error::Verification(error::VerificationError::MalformedSignature))?;
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret_key.as_bytes()).expect("HMAC accepts keys of any length.");
+    mac.update(body);
+
+    mac.verify_slice(&signature).map_err(|_| error::Error::Verification(error::VerificationError::InvalidSignature))
+}
+
+#[derive(Deserialize)]
+struct EventDocument {
+    data : WebhookEventResource,
+}
+
+impl TryFrom<EventDocument> for WebhookEventPayload {
+    type Error = String;
+
+    fn try_from(document : EventDocument) -> Result<Self, Self::Error> {
+        let transaction_id = || {
+            document.data.relationships.transaction
+            .as_ref()
+            .map(|transaction| transaction.data.id.clone())
+            .ok_or_else(|| format!("The {:?} event {} has no related transaction.", document.data.attributes.event_type, document.data.id))
+        };
+
+        let event = match document.data.attributes.event_type {
+            standard::WebhookEventTypeEnum::TransactionCreated => Event::TransactionCreated(transaction_id()?),
+            standard::WebhookEventTypeEnum::TransactionSettled => Event::TransactionSettled(transaction_id()?),
+            standard::WebhookEventTypeEnum::TransactionDeleted => Event::TransactionDeleted(transaction_id()?),
+            standard::WebhookEventTypeEnum::Ping => Event::Ping,
+            standard::WebhookEventTypeEnum::Unknown => Event::Unknown,
+        };
+
+        Ok(WebhookEventPayload {
+            data : document.data,
+            event,
+        })
+    }
 }
 
 
@@ -233,23 +336,30 @@ pub struct ListWebhooksOptions {
 }
 
 impl ListWebhooksOptions {
-    /// Sets the page size.
-    pub fn page_size(&mut self, value : u8) {
+    /// Sets the page size, which must be between 1 and 100.
+    pub fn page_size(&mut self, value : u8) -> &mut Self {
         self.page_size = Some(value);
+        self
+    }
+
+    /// Checks that the options are valid, as is done automatically before any request is made with them.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if let Some(value) = self.page_size {
+            pagination::validate_page_size(value)?;
+        }
+
+        Ok(())
     }
 
     fn add_params(&self, url : &mut reqwest::Url) {
-        let mut query = String::new();
+        let mut query = Vec::new();
 
         if let Some(value) = &self.page_size {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("page[size]={}", value));
+            query.push(("page[size]", value.to_string()));
         }
 
         if !query.is_empty() {
-            url.set_query(Some(&query));
+            url.query_pairs_mut().extend_pairs(query);
         }
     }
 }
@@ -261,29 +371,42 @@ pub struct ListWebhookLogsOptions {
 }
 
 impl ListWebhookLogsOptions {
-    /// Sets the page size.
-    pub fn page_size(&mut self, value : u8) {
+    /// Sets the page size, which must be between 1 and 100.
+    pub fn page_size(&mut self, value : u8) -> &mut Self {
         self.page_size = Some(value);
+        self
+    }
+
+    /// Checks that the options are valid, as is done automatically before any request is made with them.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if let Some(value) = self.page_size {
+            pagination::validate_page_size(value)?;
+        }
+
+        Ok(())
     }
 
     fn add_params(&self, url : &mut reqwest::Url) {
-        let mut query = String::new();
+        let mut query = Vec::new();
 
         if let Some(value) = &self.page_size {
-            if !query.is_empty() {
-                query.push('&');
-            }
-            query.push_str(&format!("page[size]={}", value));
+            query.push(("page[size]", value.to_string()));
         }
 
         if !query.is_empty() {
-            url.set_query(Some(&query));
+            url.query_pairs_mut().extend_pairs(query);
         }
     }
 }
 
 // ----------------- Request Objects -----------------
 
+/// The maximum length of the URL of a webhook.
+pub const MAX_WEBHOOK_URL_LENGTH : usize = 300;
+
+/// The maximum length of the description of a webhook.
+pub const MAX_WEBHOOK_DESCRIPTION_LENGTH : usize = 64;
+
 #[derive(Serialize)]
 pub struct CreateWebhookRequest {
     /// The webhook resource to create.
@@ -306,62 +429,51 @@ pub struct InputAttributes {
 impl Client {
     ///  Retrieve a list of configured webhooks. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present. Results are ordered oldest first to newest last.
     pub async fn list_webhooks(&self, options : &ListWebhooksOptions) -> Result<ListWebhooksResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/webhooks", BASE_URL)).map_err(error::Error::UrlParse)?;
+        options.validate()?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/webhooks", self.base_url)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let webhook_response : ListWebhooksResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let webhook_response : ListWebhooksResponse = error::from_json(&res.body)?;
 
                 Ok(webhook_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
+    /// Retrieve every configured webhook as a stream, following the `next` link of each page as it is needed. Results are ordered oldest first to newest last. The `limits` can be used to cap the total number of webhooks or pages retrieved.
+    pub fn list_webhooks_stream<'a>(&'a self, options : &ListWebhooksOptions, limits : pagination::StreamLimits) -> impl Stream<Item = Result<WebhookResource, error::Error>> + 'a {
+        let url = options
+            .validate()
+            .and_then(|_| reqwest::Url::parse(&format!("{}/webhooks", self.base_url)).map_err(error::Error::UrlParse))
+            .map(|mut url| {
+                options.add_params(&mut url);
+                url
+            });
+
+        pagination::stream::<ListWebhooksResponse>(self, url, limits)
+    }
+
     /// Retrieve a specific webhook by providing its unique identifier.
-    pub async fn get_webhook(&self, id : &str) -> Result<GetWebhookResponse, error::Error> {
-        // This assertion is because without an ID the request is thought to be a request for
-        // many webhooks, and therefore the error messages are very unclear.
-        if id.is_empty() {
-            panic!("The provided webhook ID must not be empty.");
-        }
+    pub async fn get_webhook(&self, id : &standard::WebhookId) -> Result<GetWebhookResponse, error::Error> {
+        validate_id("webhook ID", id.as_str())?;
 
-        let url = reqwest::Url::parse(&format!("{}/webhooks/{}", BASE_URL, id)).map_err(error::Error::UrlParse)?;
+        let url = reqwest::Url::parse(&format!("{}/webhooks/{}", self.base_url, id)).map_err(error::Error::UrlParse)?;
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let webhook_response : GetWebhookResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let webhook_response : GetWebhookResponse = error::from_json(&res.body)?;
 
                 Ok(webhook_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
@@ -370,7 +482,15 @@ impl Client {
     /// Event delivery is retried with exponential backoff if the URL is unreachable or it does not respond with a `200` status. The response includes a `secretKey` attribute, which is used to sign requests sent to the webhook URL. It will not be returned from any other endpoints within the Up API. If the `secretKey` is lost, simply create a new webhook with the same URL, capture its `secretKey` and then delete the original webhook. See Handling webhook events for details on how to process webhook events.
     /// It is probably a good idea to test the webhook by sending it a `PING` event after creating it.
     pub async fn create_webhook(&self, webhook_url : &str, description : Option<String>) -> Result<CreateWebhookResponse, error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/webhooks", BASE_URL)).map_err(error::Error::UrlParse)?;
+        if webhook_url.is_empty() || webhook_url.chars().count() > MAX_WEBHOOK_URL_LENGTH {
+            return Err(error::Error::InvalidArgument(format!("The webhook URL must be between 1 and {} characters in length.", MAX_WEBHOOK_URL_LENGTH)));
+        }
+
+        if description.as_ref().is_some_and(|d| d.chars().count() > MAX_WEBHOOK_DESCRIPTION_LENGTH) {
+            return Err(error::Error::InvalidArgument(format!("The webhook description must be at most {} characters in length.", MAX_WEBHOOK_DESCRIPTION_LENGTH)));
+        }
+
+        let url = reqwest::Url::parse(&format!("{}/webhooks", self.base_url)).map_err(error::Error::UrlParse)?;
 
         let body = CreateWebhookRequest {
             data : WebhookInputResource {
@@ -380,113 +500,146 @@ impl Client {
 
         let body = serde_json::to_string(&body).map_err(error::Error::Serialize)?;
 
-        let res = reqwest::Client::new()
-            .post(url)
-            .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::POST, url, Some(body)).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::CREATED => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let webhook_response : CreateWebhookResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let webhook_response : CreateWebhookResponse = error::from_json(&res.body)?;
 
                 Ok(webhook_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Delete a specific webhook by providing its unique identifier. Once deleted, webhook events will no longer be sent to the configured URL.
-    pub async fn delete_webhook(&self, id : &str) -> Result<(), error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/webhooks/{}", BASE_URL, id)).map_err(error::Error::UrlParse)?;
+    pub async fn delete_webhook(&self, id : &standard::WebhookId) -> Result<(), error::Error> {
+        validate_id("webhook ID", id.as_str())?;
 
-        let res = reqwest::Client::new()
-            .delete(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let url = reqwest::Url::parse(&format!("{}/webhooks/{}", self.base_url, id)).map_err(error::Error::UrlParse)?;
 
-        match res.status() {
+        let res = self.send(reqwest::Method::DELETE, url, None).await?;
+
+        match res.status {
             reqwest::StatusCode::NO_CONTENT => {
                 Ok(())
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Send a `PING` event to a webhook by providing its unique identifier. This is useful for testing and debugging purposes. The event is delivered asynchronously and its data is returned in the response to this request.
-    pub async fn ping_webhook(&self, id : &str) -> Result<PingWebhookResponse, error::Error> {
-        let url = reqwest::Url::parse(&format!("{}/webhooks/{}/ping", BASE_URL, id)).map_err(error::Error::UrlParse)?;
-
-        let res = reqwest::Client::new()
-            .post(url)
-            .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .header("Content-Length", "0")
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
-
-        match res.status() {
+    pub async fn ping_webhook(&self, id : &standard::WebhookId) -> Result<PingWebhookResponse, error::Error> {
+        validate_id("webhook ID", id.as_str())?;
+
+        let url = reqwest::Url::parse(&format!("{}/webhooks/{}/ping", self.base_url, id)).map_err(error::Error::UrlParse)?;
+
+        let res = self.send(reqwest::Method::POST, url, Some(String::new())).await?;
+
+        match res.status {
             reqwest::StatusCode::CREATED => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let webhook_response : PingWebhookResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let webhook_response : PingWebhookResponse = error::from_json(&res.body)?;
 
                 Ok(webhook_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 
     /// Retrieve a list of delivery logs for a webhook by providing its unique identifier. This is useful for analysis and debugging purposes. The returned list is paginated and can be scrolled by following the `next` and `prev` links where present. Results are ordered newest first to oldest last. Logs may be automatically purged after a period of time.
-    pub async fn list_webhook_logs(&self, id : &str, options : &ListWebhookLogsOptions) -> Result<ListWebhookLogsResponse, error::Error> {
-        let mut url = reqwest::Url::parse(&format!("{}/webhooks/{}/logs", BASE_URL, id)).map_err(error::Error::UrlParse)?;
+    pub async fn list_webhook_logs(&self, id : &standard::WebhookId, options : &ListWebhookLogsOptions) -> Result<ListWebhookLogsResponse, error::Error> {
+        validate_id("webhook ID", id.as_str())?;
+        options.validate()?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/webhooks/{}/logs", self.base_url, id)).map_err(error::Error::UrlParse)?;
         options.add_params(&mut url);
 
-        let res = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(error::Error::Request)?;
+        let res = self.send(reqwest::Method::GET, url, None).await?;
 
-        match res.status() {
+        match res.status {
             reqwest::StatusCode::OK => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let webhook_response : ListWebhookLogsResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
+                let webhook_response : ListWebhookLogsResponse = error::from_json(&res.body)?;
 
                 Ok(webhook_response)
             },
-            _ => {
-                let body = res.text().await.map_err(error::Error::BodyRead)?;
-                let error : error::ErrorResponse = serde_json::from_str(&body).map_err(error::Error::Json)?;
-
-                Err(error::Error::Api(error))
-            }
+            _ => Err(error::Error::from_response(res)),
         }
     }
 }
 
 // ----------------- Page Navigation -----------------
 
-implement_pagination_v1!(ListWebhooksResponse);
+pagination::implement_paginated!(ListWebhooksResponse, WebhookResource);
+
+pagination::implement_paginated!(ListWebhookLogsResponse, WebhookDeliveryLogResource);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::test_util::{SECRET, event, sign};
+
+    #[test]
+    fn events_carry_their_transaction() {
+        let created = WebhookEventPayload::parse(&event("b3e8f0c4-event", "TRANSACTION_CREATED", Some("transaction-1"))).unwrap();
+        assert_eq!(created.event, Event::TransactionCreated(standard::TransactionId::from("transaction-1")));
+        assert_eq!(created.id(), "b3e8f0c4-event");
+
+        let settled = WebhookEventPayload::parse(&event("b3e8f0c4-event", "TRANSACTION_SETTLED", Some("transaction-2"))).unwrap();
+        assert_eq!(settled.event.transaction_id().unwrap().as_str(), "transaction-2");
+
+        let deleted = WebhookEventPayload::parse(&event("b3e8f0c4-event", "TRANSACTION_DELETED", Some("transaction-3"))).unwrap();
+        assert!(matches!(deleted.event, Event::TransactionDeleted(_)));
+
+        let ping = WebhookEventPayload::parse(&event("b3e8f0c4-event", "PING", None)).unwrap();
+        assert_eq!(ping.event, Event::Ping);
+        assert_eq!(ping.data.relationships.webhook.data.id.as_str(), "webhook-1");
+    }
+
+    #[test]
+    fn signatures_match_test_vectors() {
+        let key = "key";
+        let body = b"The quick brown fox jumps over the lazy dog";
+
+        assert!(verify_signature(body, "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8", key).is_ok());
+        assert!(verify_signature(body, "F7BC83F430538424B13298E6AA6FB143EF4D59A14946175997479DBC2D1A3CD8\n", key).is_ok());
+
+        let failure = |signature : &str, body : &[u8]| match verify_signature(body, signature, key) {
+            Err(error::Error::Verification(reason)) => reason,
+            other => panic!("{:?}", other),
+        };
+
+        assert_eq!(failure("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd9", body), error::VerificationError::InvalidSignature);
+        assert_eq!(failure("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8", b"The quick brown fox jumps over the lazy dog."), error::VerificationError::InvalidSignature);
+        assert_eq!(failure("f7bc83f4", body), error::VerificationError::InvalidSignature);
+        assert_eq!(failure("not hex", body), error::VerificationError::MalformedSignature);
+    }
+
+    #[test]
+    fn verified_payloads_are_parsed() {
+        let body = event("b3e8f0c4-event", "TRANSACTION_SETTLED", Some("transaction-2"));
+        let signature = sign(&body);
+
+        let payload = WebhookEventPayload::verify(body.as_bytes(), Some(&signature), SECRET).unwrap();
+        assert_eq!(payload.event, Event::TransactionSettled(standard::TransactionId::from("transaction-2")));
+
+        assert!(matches!(WebhookEventPayload::verify(body.as_bytes(), None, SECRET), Err(error::Error::Verification(error::VerificationError::MissingSignature))));
+        assert!(WebhookEventPayload::verify(body.as_bytes(), Some(&signature), "wrong").unwrap_err().is_verification());
+    }
+
+    #[test]
+    fn unknown_event_types_are_parsed() {
+        let unknown = WebhookEventPayload::parse(&event("b3e8f0c4-event", "ACCOUNT_CREATED", None)).unwrap();
+        assert_eq!(unknown.event, Event::Unknown);
+        assert!(matches!(unknown.data.attributes.event_type, standard::WebhookEventTypeEnum::Unknown));
+        assert_eq!(unknown.event.transaction_id(), None);
+
+        let with_transaction = WebhookEventPayload::parse(&event("b3e8f0c4-event", "TRANSACTION_SPLIT", Some("transaction-1"))).unwrap();
+        assert_eq!(with_transaction.event, Event::Unknown);
+    }
+
+    #[test]
+    fn malformed_events_are_errors() {
+        assert!(matches!(WebhookEventPayload::parse(&event("b3e8f0c4-event", "TRANSACTION_CREATED", None)), Err(error::Error::Json(..))));
+        assert!(matches!(WebhookEventPayload::parse("not json"), Err(error::Error::Json(..))));
+    }
+}